@@ -63,6 +63,7 @@ pub struct Config {
 
     #[cfg(feature = "otel")]
     #[serde(default)]
+    #[builder(default)]
     pub otel_mode: crate::services::otel::OtelMode,
 }
 
@@ -17,6 +17,8 @@ pub use reqwest::StatusCode;
 
 mod config;
 pub mod services;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use config::{Config, ConfigBuilder, ConfigBuilderError};
 pub use services::ServiceFactory;
@@ -42,6 +44,36 @@ pub enum Error {
     #[error(transparent)]
     Kafka(#[from] rdkafka::error::KafkaError),
 
+    #[cfg(feature = "otel")]
+    #[error(transparent)]
+    OtelExporter(#[from] opentelemetry_otlp::ExporterBuildError),
+
+    #[cfg(feature = "testing")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "testing")]
+    #[error("Cassette mismatch: {0}")]
+    Cassette(String),
+
+    #[error("Workspace {0} is disabled")]
+    WorkspaceDisabled(uuid::Uuid),
+
+    #[error("Workspace {workspace} failed: {message}")]
+    WorkspaceFailed {
+        workspace: uuid::Uuid,
+        message: String,
+    },
+
+    #[error("Workspace {0} did not become active in time")]
+    WorkspaceWaitTimeout(uuid::Uuid),
+
+    #[error("Connection was lost after the call was sent, it may have been applied")]
+    CallInterrupted,
+
+    #[error("Could not reconnect to the transactor in time")]
+    ReconnectTimeout,
+
     #[error("Subscription task panicked")]
     SubscriptionFailed,
     #[error("Subscription task lagged and was forcibly disconnected")]
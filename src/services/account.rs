@@ -13,26 +13,37 @@
 // limitations under the License.
 //
 
+use std::pin::pin;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt, stream};
 use reqwest_middleware::ClientWithMiddleware as HttpClient;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use url::Url;
 use uuid::Uuid;
+#[cfg(target_family = "wasm")]
+use wasmtimer::tokio::{sleep, timeout};
+
+#[cfg(not(target_family = "wasm"))]
+use tokio::time::{sleep, timeout};
 
 use crate::config::Config;
 use crate::services::core::classes::Timestamp;
-use crate::services::core::{AccountUuid, PersonId, PersonUuid, WorkspaceDataId, WorkspaceUuid};
+use crate::services::core::{
+    AccountRole, AccountUuid, PersonId, PersonUuid, WorkspaceDataId, WorkspaceUuid,
+};
 use crate::{
     Error, Result,
     services::{
         ServiceClient,
-        core::{SocialId, SocialIdType},
+        core::{SocialId, SocialIdType, SocialKey},
         jwt::Claims,
     },
 };
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginInfo {
     pub account: PersonUuid,
@@ -41,7 +52,7 @@ pub struct LoginInfo {
     pub token: Option<String>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountSocialId {
     #[serde(flatten)]
@@ -60,6 +71,41 @@ pub struct AddSocialIdToPersonParams {
     pub confirmed: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseSocialIdParams {
+    /// Owner of the social id, the current account when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub person_uuid: Option<PersonUuid>,
+
+    pub r#type: SocialIdType,
+    pub value: String,
+
+    /// Also removes integrations bound to the social id
+    #[serde(default)]
+    pub delete_integrations: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SocialIdParams {
+    pub social_id: PersonId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePersonsParams {
+    pub primary_person: PersonUuid,
+    pub secondary_person: PersonUuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeAccountsParams {
+    pub primary_account: AccountUuid,
+    pub secondary_account: AccountUuid,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IntegrationKey {
@@ -246,7 +292,7 @@ pub struct SelectWorkspaceParams {
     pub external_regions: Vec<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceLoginInfo {
     #[serde(flatten)]
@@ -279,6 +325,14 @@ pub struct EnsurePersonParams {
     pub last_name: Option<String>,
 }
 
+impl EnsurePersonParamsBuilder {
+    /// Sets the social type and normalized value
+    pub fn social_key(&mut self, key: SocialKey) -> &mut Self {
+        let (social_type, social_value) = key.into_parts();
+        self.social_type(social_type).social_value(social_value)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct EnsurePersonResult {
@@ -310,6 +364,56 @@ pub struct LoginParams {
     pub password: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginOtpParams {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignUpOtpParams {
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpInfo {
+    pub sent: bool,
+
+    /// When another code can be requested
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub retry_on: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateOtpParams {
+    pub email: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordParams {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPasswordResetParams {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePasswordParams {
+    pub password: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountInfo {
@@ -325,6 +429,258 @@ pub struct ListAccountsParams {
     pub limit: Option<u32>,
 }
 
+/// Account returned by [`AccountClient::list_accounts`]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListedAccount {
+    pub uuid: AccountUuid,
+
+    #[serde(default)]
+    pub first_name: String,
+
+    #[serde(default)]
+    pub last_name: String,
+
+    #[serde(flatten)]
+    pub info: AccountInfo,
+
+    #[serde(default)]
+    pub social_ids: Vec<SocialId>,
+}
+
+impl ListedAccount {
+    pub fn name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+            .trim()
+            .to_owned()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, derive_builder::Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteParams {
+    /// Milliseconds until the invite expires, `-1` for never
+    #[builder(default = "-1")]
+    pub exp: i64,
+
+    /// Pattern the joining email must match, empty for any
+    #[builder(setter(into), default)]
+    pub email_mask: String,
+
+    /// Number of times the invite can be used, `-1` for unlimited
+    #[builder(default = "-1")]
+    pub limit: i32,
+
+    #[builder(default = "AccountRole::USER")]
+    pub role: AccountRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, derive_builder::Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteLinkParams {
+    #[builder(setter(into))]
+    pub email: String,
+
+    #[builder(default = "AccountRole::USER")]
+    pub role: AccountRole,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(strip_option), default)]
+    pub auto_join: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub first_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub last_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub navigate_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(strip_option), default)]
+    pub exp_hours: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteParams {
+    pub invite_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinParams {
+    pub email: String,
+    pub password: String,
+    pub invite_id: String,
+    pub workspace_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignUpJoinParams {
+    pub email: String,
+    pub password: String,
+    pub first: String,
+    pub last: String,
+    pub invite_id: String,
+    pub workspace_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendInviteParams {
+    pub email: String,
+    pub role: AccountRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    pub person: AccountUuid,
+    pub role: AccountRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceRoleParams {
+    pub target_account: AccountUuid,
+    pub target_role: AccountRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveWorkspaceParams {
+    pub account: AccountUuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspacesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WorkspaceMode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceNameParams {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkspaceOperation {
+    Archive,
+    Unarchive,
+    Delete,
+    MigrateTo,
+    ResetAttempts,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformWorkspaceOperationParams {
+    pub workspace_id: Vec<WorkspaceUuid>,
+    pub event: WorkspaceOperation,
+
+    /// Operation arguments, the target region for `MigrateTo`
+    #[serde(default)]
+    pub params: Vec<serde_json::Value>,
+}
+
+/// Progress reported by the workspace service
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkspaceUpdateEvent {
+    Ping,
+    CreateStarted,
+    UpgradeStarted,
+    Progress,
+    CreateDone,
+    UpgradeDone,
+    ArchivingBackupStarted,
+    ArchivingBackupDone,
+    ArchivingCleanStarted,
+    ArchivingCleanDone,
+    ArchivingDone,
+    DeleteStarted,
+    DeleteDone,
+    MigrateBackupStarted,
+    MigrateBackupDone,
+    MigrateCleanStarted,
+    MigrateCleanDone,
+    RestoreStarted,
+    RestoreDone,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceInfoParams {
+    pub workspace_uuid: WorkspaceUuid,
+    pub event: WorkspaceUpdateEvent,
+    pub version: WorkspaceVersion,
+    pub progress: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Page size of [`AccountClient::list_accounts_stream`]
+const LIST_ACCOUNTS_PAGE: u32 = 100;
+
+/// Interval between polls of [`AccountClient::watch_workspace`]
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Failed processing attempts after which the platform's workspace service gives up on a
+/// workspace in its default configuration. Deployments may configure a different limit
+pub const DEFAULT_MAX_PROCESSING_ATTEMPTS: u32 = 3;
+
+impl WorkspaceStatus {
+    /// Whether the workspace is active, or an error if it will not become active. Processing
+    /// is considered failed after `max_attempts`, if given
+    fn check_active(&self, workspace: WorkspaceUuid, max_attempts: Option<u32>) -> Result<bool> {
+        use WorkspaceMode::*;
+
+        if self.is_disabled == Some(true) {
+            return Err(Error::WorkspaceDisabled(workspace));
+        }
+
+        let failed = |message: String| Error::WorkspaceFailed { workspace, message };
+
+        match self.mode {
+            Some(Active) => Ok(true),
+
+            Some(
+                mode @ (PendingDeletion
+                | Deleting
+                | Deleted
+                | ArchingPendingBackup
+                | ArchivingBackup
+                | ArchivingPendingClean
+                | ArchivingClean
+                | Archived),
+            ) => Err(failed(format!("workspace is in {mode:?} mode"))),
+
+            _ if max_attempts
+                .is_some_and(|max| self.processing_attempts.unwrap_or_default() >= max) =>
+            {
+                Err(failed(self.processing_message.clone().unwrap_or_else(
+                    || String::from("processing attempts exhausted"),
+                )))
+            }
+
+            _ => Ok(false),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AccountClient {
     pub account: Option<AccountUuid>,
@@ -428,6 +784,42 @@ impl AccountClient {
         self.http.service(self, "getLoginInfoByToken", ()).await
     }
 
+    /// Emails a one-time login code
+    pub async fn login_otp(&self, params: &LoginOtpParams) -> Result<OtpInfo> {
+        self.http.service(self, "loginOtp", params).await
+    }
+
+    /// Emails a one-time code to create an account
+    pub async fn sign_up_otp(&self, params: &SignUpOtpParams) -> Result<OtpInfo> {
+        self.http.service(self, "signUpOtp", params).await
+    }
+
+    /// Logs in with a code sent by [`Self::login_otp`] or [`Self::sign_up_otp`]
+    pub async fn validate_otp(&self, params: &ValidateOtpParams) -> Result<LoginInfo> {
+        self.http.service(self, "validateOtp", params).await
+    }
+
+    pub async fn change_password(&self, params: &ChangePasswordParams) -> Result<()> {
+        self.http.service(self, "changePassword", params).await
+    }
+
+    /// Emails a link to reset the password
+    pub async fn request_password_reset(&self, params: &RequestPasswordResetParams) -> Result<()> {
+        self.http
+            .service(self, "requestPasswordReset", params)
+            .await
+    }
+
+    /// Sets a new password, authorized by the token from the reset link
+    pub async fn restore_password(&self, params: &RestorePasswordParams) -> Result<LoginInfo> {
+        self.http.service(self, "restorePassword", params).await
+    }
+
+    /// Confirms the email, authorized by the token from the confirmation link
+    pub async fn confirm(&self) -> Result<LoginInfo> {
+        self.http.service(self, "confirm", ()).await
+    }
+
     pub async fn get_social_ids(&self, confirmed: bool) -> Result<Vec<AccountSocialId>> {
         self.http
             .service(self, "getSocialIds", json!({"confirmed": confirmed}))
@@ -441,6 +833,38 @@ impl AccountClient {
         self.http.service(self, "addSocialIdToPerson", params).await
     }
 
+    /// Detaches a social id from its person so it can be claimed again, returning the released id
+    pub async fn release_social_id(
+        &self,
+        params: &ReleaseSocialIdParams,
+    ) -> Result<AccountSocialId> {
+        self.http.service(self, "releaseSocialId", params).await
+    }
+
+    /// Marks a social id deleted, returning it
+    pub async fn delete_social_id(&self, params: &SocialIdParams) -> Result<AccountSocialId> {
+        self.http.service(self, "deleteSocialId", params).await
+    }
+
+    /// Marks a social id verified, returning it
+    pub async fn verify_social_id(&self, params: &SocialIdParams) -> Result<SocialId> {
+        self.http.service(self, "verifySocialId", params).await
+    }
+
+    /// Moves the social ids of the secondary person to the primary one
+    pub async fn merge_persons(&self, params: &MergePersonsParams) -> Result<()> {
+        self.http
+            .service(self, "mergeSpecifiedPersons", params)
+            .await
+    }
+
+    /// Merges the secondary account, with its person and workspaces, into the primary one
+    pub async fn merge_accounts(&self, params: &MergeAccountsParams) -> Result<()> {
+        self.http
+            .service(self, "mergeSpecifiedAccounts", params)
+            .await
+    }
+
     pub async fn list_integrations(
         &self,
         params: &PartialIntegrationKey,
@@ -483,9 +907,42 @@ impl AccountClient {
         self.http.service(self, "login", params).await
     }
 
+    /// Creates an invite to the current workspace, returning its id
+    pub async fn create_invite(&self, params: &CreateInviteParams) -> Result<String> {
+        self.http.service(self, "createInvite", params).await
+    }
+
+    /// Creates a personal invite to the current workspace, returning the link to join it
+    pub async fn create_invite_link(&self, params: &CreateInviteLinkParams) -> Result<String> {
+        self.http.service(self, "createInviteLink", params).await
+    }
+
+    /// Workspace the invite grants access to, fails if it is expired or used up
+    pub async fn check_invite(&self, params: &InviteParams) -> Result<WorkspaceUuid> {
+        self.http.service(self, "checkInvite", params).await
+    }
+
+    /// Joins the invite's workspace with an existing account
+    pub async fn join(&self, params: &JoinParams) -> Result<WorkspaceLoginInfo> {
+        self.http.service(self, "join", params).await
+    }
+
+    /// Creates an account and joins the invite's workspace with it
+    pub async fn sign_up_join(&self, params: &SignUpJoinParams) -> Result<WorkspaceLoginInfo> {
+        self.http.service(self, "signUpJoin", params).await
+    }
+
+    pub async fn resend_invite(&self, params: &ResendInviteParams) -> Result<()> {
+        self.http.service(self, "resendInvite", params).await
+    }
+
+    pub async fn revoke_invite(&self, params: &InviteParams) -> Result<()> {
+        self.http.service(self, "revokeInvite", params).await
+    }
+
     pub async fn find_person_by_social_key(
         &self,
-        key: &str,
+        key: &SocialKey,
         require_account: bool,
     ) -> Result<Option<Uuid>> {
         let params = json!({"socialString": key, "requireAccount": require_account});
@@ -507,7 +964,7 @@ impl AccountClient {
 
     pub async fn find_social_id_by_social_key(
         &self,
-        key: &str,
+        key: &SocialKey,
         require_account: bool,
     ) -> Result<Option<String>> {
         let params = json!({"socialKey": key, "requireAccount": require_account});
@@ -520,15 +977,228 @@ impl AccountClient {
         self.http.service(self, "getUserWorkspaces", ()).await
     }
 
+    /// All workspaces with their status, requires a system token
+    pub async fn list_workspaces(
+        &self,
+        params: &ListWorkspacesParams,
+    ) -> Result<Vec<WorkspaceInfoWithStatus>> {
+        self.http.service(self, "listWorkspaces", params).await
+    }
+
+    /// Info and status of the current workspace
+    pub async fn get_workspace_info(
+        &self,
+        update_last_visit: bool,
+    ) -> Result<WorkspaceInfoWithStatus> {
+        let params = json!({"updateLastVisit": update_last_visit});
+        self.http.service(self, "getWorkspaceInfo", params).await
+    }
+
+    pub async fn get_workspaces_info(
+        &self,
+        ids: &[WorkspaceUuid],
+    ) -> Result<Vec<WorkspaceInfoWithStatus>> {
+        let params = json!({"ids": ids});
+        self.http.service(self, "getWorkspacesInfo", params).await
+    }
+
+    async fn workspace_status(&self, workspace: WorkspaceUuid) -> Result<WorkspaceStatus> {
+        self.get_workspaces_info(&[workspace])
+            .await?
+            .into_iter()
+            .next()
+            .map(|info| info.status)
+            .ok_or(Error::Other("WorkspaceNotFound"))
+    }
+
+    /// Polls the status of a workspace, yielding it on every change. Ends after the first error.
+    pub fn watch_workspace(
+        &self,
+        workspace: WorkspaceUuid,
+    ) -> impl Stream<Item = Result<WorkspaceStatus>> + '_ {
+        stream::unfold(
+            Some(None),
+            move |last: Option<Option<WorkspaceStatus>>| async move {
+                let last = last?;
+
+                loop {
+                    if last.is_some() {
+                        sleep(WATCH_INTERVAL).await;
+                    }
+
+                    match self.workspace_status(workspace).await {
+                        Ok(status) if last.as_ref() == Some(&status) => {}
+                        Ok(status) => return Some((Ok(status.clone()), Some(Some(status)))),
+                        Err(error) => return Some((Err(error), None)),
+                    }
+                }
+            },
+        )
+    }
+
+    /// Waits until a workspace is active, failing early if it is disabled, or its processing
+    /// failed `max_attempts` times, see [`DEFAULT_MAX_PROCESSING_ATTEMPTS`]
+    pub async fn wait_until_active(
+        &self,
+        workspace: WorkspaceUuid,
+        wait: Duration,
+        max_attempts: Option<u32>,
+    ) -> Result<WorkspaceStatus> {
+        let active = async {
+            let mut statuses = pin!(self.watch_workspace(workspace));
+
+            while let Some(status) = statuses.next().await {
+                let status = status?;
+
+                if status.check_active(workspace, max_attempts)? {
+                    return Ok(status);
+                }
+            }
+
+            Err(Error::Other("WorkspaceNotFound"))
+        };
+
+        timeout(wait, active)
+            .await
+            .map_err(|_| Error::WorkspaceWaitTimeout(workspace))?
+    }
+
+    pub async fn update_workspace_name(&self, params: &UpdateWorkspaceNameParams) -> Result<()> {
+        self.http.service(self, "updateWorkspaceName", params).await
+    }
+
+    /// Deletes the current workspace
+    pub async fn delete_workspace(&self) -> Result<()> {
+        self.http.service(self, "deleteWorkspace", ()).await
+    }
+
+    /// Schedules a lifecycle operation, returns whether it was accepted
+    pub async fn perform_workspace_operation(
+        &self,
+        params: &PerformWorkspaceOperationParams,
+    ) -> Result<bool> {
+        self.http
+            .service(self, "performWorkspaceOperation", params)
+            .await
+    }
+
+    pub async fn archive_workspaces(&self, workspaces: &[WorkspaceUuid]) -> Result<bool> {
+        self.workspace_operation(workspaces, WorkspaceOperation::Archive, Vec::new())
+            .await
+    }
+
+    pub async fn restore_workspaces(&self, workspaces: &[WorkspaceUuid]) -> Result<bool> {
+        self.workspace_operation(workspaces, WorkspaceOperation::Unarchive, Vec::new())
+            .await
+    }
+
+    pub async fn delete_workspaces(&self, workspaces: &[WorkspaceUuid]) -> Result<bool> {
+        self.workspace_operation(workspaces, WorkspaceOperation::Delete, Vec::new())
+            .await
+    }
+
+    pub async fn migrate_workspaces(
+        &self,
+        workspaces: &[WorkspaceUuid],
+        region: &str,
+    ) -> Result<bool> {
+        self.workspace_operation(
+            workspaces,
+            WorkspaceOperation::MigrateTo,
+            vec![region.into()],
+        )
+        .await
+    }
+
+    async fn workspace_operation(
+        &self,
+        workspaces: &[WorkspaceUuid],
+        event: WorkspaceOperation,
+        params: Vec<serde_json::Value>,
+    ) -> Result<bool> {
+        self.perform_workspace_operation(&PerformWorkspaceOperationParams {
+            workspace_id: workspaces.to_vec(),
+            event,
+            params,
+        })
+        .await
+    }
+
+    /// Reports workspace processing, updating its `WorkspaceStatus`
+    pub async fn update_workspace_info(&self, params: &UpdateWorkspaceInfoParams) -> Result<()> {
+        self.http.service(self, "updateWorkspaceInfo", params).await
+    }
+
+    /// Members of the current workspace with their roles
+    pub async fn get_workspace_members(&self) -> Result<Vec<WorkspaceMember>> {
+        self.http.service(self, "getWorkspaceMembers", ()).await
+    }
+
+    pub async fn update_workspace_role(&self, params: &UpdateWorkspaceRoleParams) -> Result<()> {
+        self.http.service(self, "updateWorkspaceRole", params).await
+    }
+
+    /// Removes another account from the current workspace
+    pub async fn remove_workspace_member(&self, params: &LeaveWorkspaceParams) -> Result<()> {
+        self.http.service(self, "leaveWorkspace", params).await
+    }
+
+    /// Removes the current account from the current workspace
+    pub async fn leave_workspace(&self) -> Result<()> {
+        let account = self.account.ok_or(Error::Other("NoAccount"))?;
+
+        self.remove_workspace_member(&LeaveWorkspaceParams { account })
+            .await
+    }
+
     pub async fn get_account_info(&self, account_uuid: &AccountUuid) -> Result<AccountInfo> {
         let params = json!({"accountId": account_uuid});
         self.http.service(self, "getAccountInfo", params).await
     }
 
-    pub async fn list_accounts(&self, params: &ListAccountsParams) -> Result<Vec<AccountInfo>> {
+    pub async fn list_accounts(&self, params: &ListAccountsParams) -> Result<Vec<ListedAccount>> {
         self.http.service(self, "listAccounts", params).await
     }
 
+    /// Lists all accounts matching `search`, fetching pages as the stream is consumed
+    pub fn list_accounts_stream(
+        &self,
+        search: Option<&str>,
+    ) -> impl Stream<Item = Result<ListedAccount>> + '_ {
+        let search = search.map(ToOwned::to_owned);
+
+        stream::unfold(Some(0), move |skip| {
+            let search = search.clone();
+
+            async move {
+                let skip = skip?;
+
+                let params = ListAccountsParams {
+                    search,
+                    skip: Some(skip),
+                    limit: Some(LIST_ACCOUNTS_PAGE),
+                };
+
+                match self.list_accounts(&params).await {
+                    Ok(page) => {
+                        let next = (page.len() as u32 == LIST_ACCOUNTS_PAGE)
+                            .then_some(skip + LIST_ACCOUNTS_PAGE);
+
+                        Some((Ok(page), next))
+                    }
+
+                    Err(error) => Some((Err(error), None)),
+                }
+            }
+        })
+        .flat_map(|page| {
+            stream::iter(match page {
+                Ok(page) => page.into_iter().map(Ok).collect(),
+                Err(error) => vec![Err(error)],
+            })
+        })
+    }
+
     pub async fn add_integration_secret(&self, secret: &IntegrationSecret) -> Result<()> {
         self.http
             .service(self, "addIntegrationSecret", secret)
@@ -556,3 +1226,68 @@ impl AccountClient {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(mode: WorkspaceMode) -> WorkspaceStatus {
+        WorkspaceStatus {
+            version: WorkspaceVersion {
+                version_major: 0,
+                version_minor: 7,
+                version_patch: 0,
+            },
+            mode: Some(mode),
+            processing_progress: None,
+            last_processing_time: None,
+            last_visit: None,
+            is_disabled: None,
+            processing_attempts: None,
+            processing_message: None,
+            backup_info: None,
+            target_region: None,
+        }
+    }
+
+    #[test]
+    fn test_check_active() {
+        let workspace = Uuid::nil();
+
+        assert!(
+            status(WorkspaceMode::Active)
+                .check_active(workspace, None)
+                .unwrap()
+        );
+        assert!(
+            !status(WorkspaceMode::Creating)
+                .check_active(workspace, None)
+                .unwrap()
+        );
+
+        let disabled = WorkspaceStatus {
+            is_disabled: Some(true),
+            ..status(WorkspaceMode::Active)
+        };
+        assert!(matches!(
+            disabled.check_active(workspace, None),
+            Err(Error::WorkspaceDisabled(_))
+        ));
+
+        let exhausted = WorkspaceStatus {
+            processing_attempts: Some(DEFAULT_MAX_PROCESSING_ATTEMPTS),
+            processing_message: Some(String::from("migration failed")),
+            ..status(WorkspaceMode::Upgrading)
+        };
+        assert!(matches!(
+            exhausted.check_active(workspace, Some(DEFAULT_MAX_PROCESSING_ATTEMPTS)),
+            Err(Error::WorkspaceFailed { message, .. }) if message == "migration failed"
+        ));
+        assert!(!exhausted.check_active(workspace, None).unwrap());
+
+        assert!(matches!(
+            status(WorkspaceMode::Archived).check_active(workspace, None),
+            Err(Error::WorkspaceFailed { .. })
+        ));
+    }
+}
@@ -14,6 +14,11 @@ pub mod class {
     pub const FavoriteCard: &str = "card:class:FavoriteCard";
 }
 
+#[allow(non_upper_case_globals)]
+pub mod space {
+    pub const Default: &str = "card:space:Default";
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct MasterTag {
@@ -48,7 +53,9 @@ impl HasId for MasterTag {
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ParentInfo {
+    #[serde(rename = "_id")]
     pub _id: Ref,
+    #[serde(rename = "_class")]
     pub _class: Ref,
     pub title: String,
 }
@@ -68,8 +75,11 @@ pub struct Card {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachments: Option<u32>,
     pub parent_info: Vec<ParentInfo>,
-    pub parent: Option<Box<Card>>,
+    #[serde(default)]
+    pub parent: Option<Ref>,
     pub rank: Rank,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub archived: Option<bool>,
 }
 
 impl DocT for Card {
@@ -82,6 +92,19 @@ impl Class for Card {
     const CLASS: &'static str = card::class::Card;
 }
 
+impl Card {
+    /// [`ParentInfo`] chain for cards placed directly under this one
+    pub fn child_parent_info(&self) -> Vec<ParentInfo> {
+        let mut parent_info = self.parent_info.clone();
+        parent_info.push(ParentInfo {
+            _id: self.doc.id.clone(),
+            _class: self.doc.obj.class.clone(),
+            title: self.title.clone(),
+        });
+        parent_info
+    }
+}
+
 impl HasId for Card {
     fn id(&self) -> &str {
         &self.doc().id
@@ -82,7 +82,7 @@ pub struct BasePerson {
     pub person_uuid: Option<PersonUuid>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum SocialIdType {
     Email,
@@ -96,6 +96,110 @@ pub enum SocialIdType {
     Unknown(String),
 }
 
+impl SocialIdType {
+    /// Name used as the prefix of social keys
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Email => "email",
+            Self::GitHub => "github",
+            Self::Google => "google",
+            Self::Phone => "phone",
+            Self::OIDC => "oidc",
+            Self::Huly => "huly",
+            Self::Telegram => "telegram",
+            Self::Unknown(name) => name,
+        }
+    }
+
+    /// Canonical form of a value, lowercased emails and phones without formatting
+    pub fn normalize(&self, value: &str) -> String {
+        let value = value.trim();
+
+        match self {
+            Self::Email => value.to_lowercase(),
+            Self::Phone => value
+                .chars()
+                .enumerate()
+                .filter(|(i, c)| c.is_ascii_digit() || (*i == 0 && *c == '+'))
+                .map(|(_, c)| c)
+                .collect(),
+            _ => value.to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for SocialIdType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SocialIdType {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(crate::Error::Other("InvalidSocialIdType"));
+        }
+
+        Ok(serde_json::from_value(serde_json::Value::from(s))?)
+    }
+}
+
+/// Social key, `type:value` with the value normalized for its type, e.g. `email:user@example.com`
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, serde_with::SerializeDisplay, serde_with::DeserializeFromStr,
+)]
+pub struct SocialKey {
+    r#type: SocialIdType,
+    value: String,
+}
+
+impl SocialKey {
+    pub fn new(r#type: SocialIdType, value: &str) -> Self {
+        let value = r#type.normalize(value);
+        Self { r#type, value }
+    }
+
+    pub fn email(value: &str) -> Self {
+        Self::new(SocialIdType::Email, value)
+    }
+
+    pub fn r#type(&self) -> &SocialIdType {
+        &self.r#type
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn into_parts(self) -> (SocialIdType, String) {
+        (self.r#type, self.value)
+    }
+}
+
+impl std::fmt::Display for SocialKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.r#type, self.value)
+    }
+}
+
+impl std::str::FromStr for SocialKey {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r#type, value) = s
+            .split_once(':')
+            .ok_or(crate::Error::Other("InvalidSocialKey"))?;
+
+        if value.is_empty() {
+            return Err(crate::Error::Other("InvalidSocialKey"));
+        }
+
+        Ok(Self::new(r#type.parse()?, value))
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SocialId {
@@ -114,7 +218,14 @@ pub struct SocialId {
     pub verified_on: Option<Timestamp>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+impl SocialId {
+    pub fn social_key(&self) -> SocialKey {
+        SocialKey::new(self.r#type.clone(), &self.value)
+    }
+}
+
+/// Workspace role, ordered from least to most privileged
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AccountRole {
     DocGuest,
     GUEST,
@@ -140,3 +251,33 @@ pub struct FindResult<T> {
     pub value: Vec<T>,
     pub lookup_map: Option<HashMap<String, T>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_social_key() {
+        let key: SocialKey = "email: User@Example.COM ".parse().unwrap();
+        assert_eq!(key.to_string(), "email:user@example.com");
+
+        let key: SocialKey = "phone:+1 (555) 010-99".parse().unwrap();
+        assert_eq!(key.value(), "+155501099");
+
+        let key: SocialKey = "github:Octocat".parse().unwrap();
+        assert_eq!(key.r#type(), &SocialIdType::GitHub);
+        assert_eq!(key.to_string(), "github:Octocat");
+
+        let key: SocialKey = "matrix:@user:example.org".parse().unwrap();
+        assert_eq!(key.r#type(), &SocialIdType::Unknown(String::from("matrix")));
+        assert_eq!(key.value(), "@user:example.org");
+
+        assert!("email".parse::<SocialKey>().is_err());
+        assert!(":value".parse::<SocialKey>().is_err());
+
+        assert_eq!(
+            serde_json::to_value(SocialKey::email("a@b.c")).unwrap(),
+            "email:a@b.c"
+        );
+    }
+}
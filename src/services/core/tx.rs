@@ -3,7 +3,7 @@ use crate::services::core::classes::Ref;
 use crate::services::event::{Class, Event, HasId};
 use crate::services::transactor::tx::Doc;
 use derive_builder::Builder;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -18,8 +18,7 @@ pub struct Tx {
     pub object_space: Ref,
 }
 
-#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum WorkspaceEvent {
     UpgradeScheduled = 0,
     IndexingUpdate = 1,
@@ -29,6 +28,15 @@ pub enum WorkspaceEvent {
     LastTx = 5,
 }
 
+impl Serialize for WorkspaceEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
 impl<'de> Deserialize<'de> for WorkspaceEvent {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -44,6 +52,13 @@ impl<'de> Deserialize<'de> for WorkspaceEvent {
             }
 
             fn visit_u8<E>(self, value: u8) -> Result<WorkspaceEvent, E>
+            where
+                E: serde::de::Error,
+            {
+                self.visit_u64(value as u64)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<WorkspaceEvent, E>
             where
                 E: serde::de::Error,
             {
@@ -55,7 +70,7 @@ impl<'de> Deserialize<'de> for WorkspaceEvent {
                     4 => Ok(WorkspaceEvent::BulkUpdate),
                     5 => Ok(WorkspaceEvent::LastTx),
                     _ => Err(serde::de::Error::invalid_value(
-                        serde::de::Unexpected::Unsigned(value as u64),
+                        serde::de::Unexpected::Unsigned(value),
                         &self,
                     )),
                 }
@@ -71,8 +86,71 @@ impl<'de> Deserialize<'de> for WorkspaceEvent {
 pub struct TxWorkspaceEvent<T> {
     #[serde(flatten)]
     pub tx: Tx,
-    pub domain: WorkspaceEvent,
-    pub event: T,
+    pub event: WorkspaceEvent,
+    pub params: T,
+}
+
+/// Classes affected by [`WorkspaceEvent::IndexingUpdate`] and [`WorkspaceEvent::BulkUpdate`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassesUpdate {
+    #[serde(rename = "_class", default)]
+    pub classes: Vec<Ref>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceNotification {
+    /// Minutes left until the maintenance starts
+    #[serde(default)]
+    pub time_minutes: Option<u32>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Typed `params` of a [`TxWorkspaceEvent`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkspaceEventParams {
+    UpgradeScheduled(Value),
+    IndexingUpdate(ClassesUpdate),
+    SecurityChange(Value),
+    MaintenanceNotification(MaintenanceNotification),
+    BulkUpdate(ClassesUpdate),
+    LastTx(Value),
+}
+
+impl TryFrom<TxWorkspaceEvent<Value>> for TxWorkspaceEvent<WorkspaceEventParams> {
+    type Error = serde_json::Error;
+
+    fn try_from(value: TxWorkspaceEvent<Value>) -> Result<Self, Self::Error> {
+        let params = match value.event {
+            WorkspaceEvent::UpgradeScheduled => {
+                WorkspaceEventParams::UpgradeScheduled(value.params)
+            }
+            WorkspaceEvent::IndexingUpdate => {
+                WorkspaceEventParams::IndexingUpdate(serde_json::from_value(value.params)?)
+            }
+            WorkspaceEvent::SecurityChange => WorkspaceEventParams::SecurityChange(value.params),
+            WorkspaceEvent::MaintenanceNotification => {
+                let params = if value.params.is_null() {
+                    MaintenanceNotification::default()
+                } else {
+                    serde_json::from_value(value.params)?
+                };
+
+                WorkspaceEventParams::MaintenanceNotification(params)
+            }
+            WorkspaceEvent::BulkUpdate => {
+                WorkspaceEventParams::BulkUpdate(serde_json::from_value(value.params)?)
+            }
+            WorkspaceEvent::LastTx => WorkspaceEventParams::LastTx(value.params),
+        };
+
+        Ok(TxWorkspaceEvent {
+            tx: value.tx,
+            event: value.event,
+            params,
+        })
+    }
 }
 
 impl<T: Debug> Class for TxWorkspaceEvent<T> {
@@ -244,3 +322,55 @@ impl Event for TxRemoveDoc {
         value.get("_class").and_then(|v| v.as_str()) == Some(Self::CLASS)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_workspace_event() {
+        let value = json!({
+            "_id": "tx",
+            "_class": "core:class:TxWorkspaceEvent",
+            "space": "core:space:DerivedTx",
+            "objectSpace": "core:space:DerivedTx",
+            "modifiedOn": 1,
+            "modifiedBy": "core:account:System",
+            "event": 3,
+            "params": { "timeMinutes": 5 },
+        });
+
+        let event: TxWorkspaceEvent<Value> = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(event.event, WorkspaceEvent::MaintenanceNotification);
+        assert_eq!(serde_json::to_value(&event).unwrap()["event"], json!(3));
+
+        let event = TxWorkspaceEvent::<WorkspaceEventParams>::try_from(event).unwrap();
+        assert_eq!(
+            event.params,
+            WorkspaceEventParams::MaintenanceNotification(MaintenanceNotification {
+                time_minutes: Some(5),
+                message: None,
+            })
+        );
+
+        let event: TxWorkspaceEvent<Value> = serde_json::from_value(json!({
+            "_id": "tx",
+            "_class": "core:class:TxWorkspaceEvent",
+            "space": "core:space:DerivedTx",
+            "objectSpace": "core:space:DerivedTx",
+            "modifiedOn": 1,
+            "modifiedBy": "core:account:System",
+            "event": 4,
+            "params": { "_class": ["card:class:Card"] },
+        }))
+        .unwrap();
+        assert!(matches!(
+            TxWorkspaceEvent::<WorkspaceEventParams>::try_from(event).unwrap().params,
+            WorkspaceEventParams::BulkUpdate(ClassesUpdate { classes }) if classes == ["card:class:Card"]
+        ));
+
+        assert!(serde_json::from_value::<WorkspaceEvent>(json!(9)).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::services::transactor::tx::Doc;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::fmt::Debug;
 
@@ -25,3 +26,11 @@ pub trait Event: Class {
         value.get("_class").and_then(|v| v.as_str()) == Some(Self::CLASS)
     }
 }
+
+/// Payload of a `TxDomainEvent` in a specific domain
+pub trait DomainEvent: DeserializeOwned {
+    const DOMAIN: &'static str;
+
+    /// Whether the `event` payload can be deserialized into this type
+    fn matches(event: &Value) -> bool;
+}
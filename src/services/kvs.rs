@@ -19,7 +19,7 @@ use secrecy::{ExposeSecret, SecretString};
 use url::Url;
 
 use super::jwt::Claims;
-use crate::services::{HttpClient, RequestBuilderExt};
+use crate::services::{HttpClient, RequestBuilderExt, metrics};
 use crate::{Error, Result, config::Config};
 
 pub struct KvsClient {
@@ -54,6 +54,15 @@ impl KvsClient {
     }
 
     pub async fn upsert(&self, key: &str, value: &[u8]) -> Result<()> {
+        metrics::measure(
+            metrics::service::KVS,
+            "upsert",
+            self.upsert_inner(key, value),
+        )
+        .await
+    }
+
+    async fn upsert_inner(&self, key: &str, value: &[u8]) -> Result<()> {
         let path = format!("api/{}/{}", self.namespace, key);
         let url = self.base.join(&path)?;
 
@@ -69,6 +78,10 @@ impl KvsClient {
     }
 
     pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        metrics::measure(metrics::service::KVS, "get", self.get_inner(key)).await
+    }
+
+    async fn get_inner(&self, key: &str) -> Result<Option<Vec<u8>>> {
         let path = format!("api/{}/{}", self.namespace, key);
         let url = self.base.join(&path)?;
 
@@ -93,6 +106,10 @@ impl KvsClient {
     }
 
     pub async fn delete(&self, key: &str) -> Result<()> {
+        metrics::measure(metrics::service::KVS, "delete", self.delete_inner(key)).await
+    }
+
+    async fn delete_inner(&self, key: &str) -> Result<()> {
         let path = format!("api/{}/{}", self.namespace, key);
         let url = self.base.join(&path)?;
 
@@ -0,0 +1,221 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Client metrics, recorded only with the `otel` feature
+
+use crate::{Error, Result};
+
+pub const METER: &str = "hulyrs";
+
+/// Histogram of request durations in seconds, labelled by `service`, `method` and `outcome`
+pub const REQUEST_DURATION: &str = "hulyrs.client.request.duration";
+/// Failed requests, labelled by `service`, `method` and `error`
+pub const REQUEST_ERRORS: &str = "hulyrs.client.request.errors";
+
+/// Requests sent over the websocket and waiting for a reply
+pub const WS_IN_FLIGHT: &str = "hulyrs.ws.requests.in_flight";
+/// Websocket reconnect attempts, labelled by `outcome`
+pub const WS_RECONNECTS: &str = "hulyrs.ws.reconnects";
+/// Broadcast transactions missed by slow subscribers
+pub const WS_BROADCAST_LAGGED: &str = "hulyrs.ws.broadcast.lagged";
+
+/// Histogram of Kafka delivery durations in seconds, labelled by `topic` and `outcome`
+pub const KAFKA_DELIVERY_DURATION: &str = "hulyrs.kafka.producer.delivery.duration";
+/// Messages delivered to Kafka, labelled by `topic`
+pub const KAFKA_DELIVERED: &str = "hulyrs.kafka.producer.delivered";
+/// Messages Kafka failed to deliver, labelled by `topic`
+pub const KAFKA_DELIVERY_ERRORS: &str = "hulyrs.kafka.producer.errors";
+
+pub(crate) mod service {
+    pub const ACCOUNT: &str = "account";
+    pub const KVS: &str = "kvs";
+    pub const PULSE: &str = "pulse";
+    pub const TRANSACTOR_HTTP: &str = "transactor_http";
+    pub const TRANSACTOR_WS: &str = "transactor_ws";
+}
+
+#[cfg(feature = "otel")]
+mod instruments {
+    use std::sync::LazyLock;
+
+    use opentelemetry::global::meter;
+    use opentelemetry::metrics::{Counter, Histogram, UpDownCounter};
+
+    pub static REQUEST_DURATION: LazyLock<Histogram<f64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .f64_histogram(super::REQUEST_DURATION)
+            .with_unit("s")
+            .build()
+    });
+
+    pub static REQUEST_ERRORS: LazyLock<Counter<u64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .u64_counter(super::REQUEST_ERRORS)
+            .build()
+    });
+
+    pub static WS_IN_FLIGHT: LazyLock<UpDownCounter<i64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .i64_up_down_counter(super::WS_IN_FLIGHT)
+            .build()
+    });
+
+    pub static WS_RECONNECTS: LazyLock<Counter<u64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .u64_counter(super::WS_RECONNECTS)
+            .build()
+    });
+
+    pub static WS_BROADCAST_LAGGED: LazyLock<Counter<u64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .u64_counter(super::WS_BROADCAST_LAGGED)
+            .build()
+    });
+
+    pub static KAFKA_DELIVERY_DURATION: LazyLock<Histogram<f64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .f64_histogram(super::KAFKA_DELIVERY_DURATION)
+            .with_unit("s")
+            .build()
+    });
+
+    pub static KAFKA_DELIVERED: LazyLock<Counter<u64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .u64_counter(super::KAFKA_DELIVERED)
+            .build()
+    });
+
+    pub static KAFKA_DELIVERY_ERRORS: LazyLock<Counter<u64>> = LazyLock::new(|| {
+        meter(super::METER)
+            .u64_counter(super::KAFKA_DELIVERY_ERRORS)
+            .build()
+    });
+}
+
+/// Low cardinality label for an error
+#[cfg_attr(not(feature = "otel"), allow(dead_code))]
+fn error_kind(error: &Error) -> &'static str {
+    match error {
+        Error::ServiceError(_) => "service",
+        Error::HttpError(..) => "http",
+        Error::Serde(_) => "serde",
+        Error::Reqwest(_) | Error::ReqwestMiddleware(_) | Error::Ws(_) => "transport",
+        #[cfg(feature = "kafka")]
+        Error::Kafka(_) => "kafka",
+        _ => "other",
+    }
+}
+
+#[cfg_attr(not(feature = "otel"), allow(dead_code))]
+fn outcome<T>(result: &Result<T>) -> &'static str {
+    if result.is_ok() { "ok" } else { "error" }
+}
+
+/// Awaits a request, recording its duration and failure
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub(crate) async fn measure<T>(
+    service: &'static str,
+    method: &str,
+    request: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    #[cfg(feature = "otel")]
+    let start = std::time::Instant::now();
+
+    let result = request.await;
+
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::KeyValue;
+
+        let labels = [
+            KeyValue::new("service", service),
+            KeyValue::new("method", method.to_owned()),
+        ];
+
+        instruments::REQUEST_DURATION.record(
+            start.elapsed().as_secs_f64(),
+            &[
+                labels[0].clone(),
+                labels[1].clone(),
+                KeyValue::new("outcome", outcome(&result)),
+            ],
+        );
+
+        if let Err(error) = &result {
+            let [service, method] = labels;
+            instruments::REQUEST_ERRORS.add(
+                1,
+                &[service, method, KeyValue::new("error", error_kind(error))],
+            );
+        }
+    }
+
+    result
+}
+
+/// Counts a websocket request as in flight while alive
+pub(crate) struct InFlight(());
+
+impl InFlight {
+    pub(crate) fn start() -> Self {
+        #[cfg(feature = "otel")]
+        instruments::WS_IN_FLIGHT.add(1, &[]);
+
+        Self(())
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        instruments::WS_IN_FLIGHT.add(-1, &[]);
+    }
+}
+
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub(crate) fn ws_reconnect<T>(result: &Result<T>) {
+    #[cfg(feature = "otel")]
+    instruments::WS_RECONNECTS.add(
+        1,
+        &[opentelemetry::KeyValue::new("outcome", outcome(result))],
+    );
+}
+
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub(crate) fn broadcast_lagged(missed: u64) {
+    #[cfg(feature = "otel")]
+    instruments::WS_BROADCAST_LAGGED.add(missed, &[]);
+}
+
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+#[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+pub(crate) fn kafka_delivery<T>(topic: &str, elapsed: std::time::Duration, result: &Result<T>) {
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::KeyValue;
+
+        let topic = KeyValue::new("topic", topic.to_owned());
+
+        instruments::KAFKA_DELIVERY_DURATION.record(
+            elapsed.as_secs_f64(),
+            &[topic.clone(), KeyValue::new("outcome", outcome(result))],
+        );
+
+        match result {
+            Ok(_) => instruments::KAFKA_DELIVERED.add(1, &[topic]),
+            Err(_) => instruments::KAFKA_DELIVERY_ERRORS.add(1, &[topic]),
+        }
+    }
+}
@@ -20,12 +20,14 @@ pub mod core;
 pub mod event;
 pub mod jwt;
 pub mod kvs;
+pub mod metrics;
 #[cfg(feature = "otel")]
 pub mod otel;
 pub mod platform;
 pub mod preference;
 pub mod pulse;
-mod rpc;
+pub mod rank;
+pub(crate) mod rpc;
 pub mod transactor;
 pub mod ui;
 
@@ -278,31 +280,67 @@ impl ServiceClient for HttpClient {
             req = req.bearer_auth(token);
         }
 
-        let response = req.send_ext().await?.json::<Value>().await?;
+        // JSON-RPC errors arrive with HTTP 200, so decoding is measured too
+        metrics::measure(metrics::service::ACCOUNT, method, async {
+            let response = req.send_ext().await?.json::<Value>().await?;
 
-        trace!(type="service", %url,  %response, "http response");
+            trace!(type="service", %url,  %response, "http response");
 
-        let response = from_value(response)?;
+            let response = from_value(response)?;
 
-        match json::from_value(response)? {
-            Response {
-                result: Some(result),
-                error: None,
-            } => Ok(from_value::<R>(result)?),
+            match json::from_value(response)? {
+                Response {
+                    result: Some(result),
+                    error: None,
+                } => Ok(from_value::<R>(result)?),
 
-            Response {
-                result: None,
-                error: Some(error),
-            } => Err(Error::ServiceError(from_value::<Status>(error)?)),
+                Response {
+                    result: None,
+                    error: Some(error),
+                } => Err(Error::ServiceError(from_value::<Status>(error)?)),
 
-            Response {
-                result: None,
-                error: None,
-            } => Ok(json::from_value(json::Value::Null)?),
+                Response {
+                    result: None,
+                    error: None,
+                } => Ok(json::from_value(json::Value::Null)?),
 
-            _ => Err(Error::Other("Unexpected service response")),
+                _ => Err(Error::Other("Unexpected service response")),
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "otel")]
+fn propagate_trace_context<'a>(
+    mut request: reqwest::Request,
+    extensions: &'a mut http::Extensions,
+    next: reqwest_middleware::Next<'a>,
+) -> futures::future::BoxFuture<'a, reqwest_middleware::Result<Response>> {
+    struct HeaderInjector<'a>(&'a mut reqwest::header::HeaderMap);
+
+    impl opentelemetry::propagation::Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::try_from(key),
+                HeaderValue::try_from(value),
+            ) {
+                self.0.insert(name, value);
+            }
         }
     }
+
+    otel::inject_trace_context(&mut HeaderInjector(request.headers_mut()));
+    next.run(request, extensions)
+}
+
+fn client_builder() -> ClientBuilder {
+    let builder = ClientBuilder::new(reqwest::Client::new());
+
+    #[cfg(feature = "otel")]
+    let builder = builder.with(propagate_trace_context);
+
+    builder
 }
 
 #[derive(Clone)]
@@ -320,26 +358,26 @@ impl ServiceFactory {
         let account_http = {
             let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
 
-            ClientBuilder::new(reqwest::Client::new())
+            client_builder()
                 .with(RetryTransientMiddleware::new_with_policy(retry_policy))
                 .build()
         };
 
         #[cfg(not(feature = "reqwest_middleware"))]
-        let account_http = { ClientBuilder::new(reqwest::Client::new()).build() };
+        let account_http = { client_builder().build() };
 
         #[cfg(feature = "reqwest_middleware")]
         let kvs_http = {
             let policy = ExponentialBackoff::builder()
                 .build_with_total_retry_duration(Duration::from_secs(10));
 
-            ClientBuilder::new(reqwest::Client::new())
+            client_builder()
                 .with(RetryTransientMiddleware::new_with_policy(policy))
                 .build()
         };
 
         #[cfg(not(feature = "reqwest_middleware"))]
-        let kvs_http = { ClientBuilder::new(reqwest::Client::new()).build() };
+        let kvs_http = { client_builder().build() };
 
         #[cfg(feature = "reqwest_middleware")]
         let transactor_http = {
@@ -429,27 +467,24 @@ impl ServiceFactory {
                 reqwest_ratelimit::all(Limiter::new(config.account_service_rate_limit))
             };
 
-            ClientBuilder::new(reqwest::Client::new())
-                .with(rate_limiter)
-                .with(retry)
-                .build()
+            client_builder().with(rate_limiter).with(retry).build()
         };
 
         #[cfg(not(feature = "reqwest_middleware"))]
-        let transactor_http = { ClientBuilder::new(reqwest::Client::new()).build() };
+        let transactor_http = { client_builder().build() };
 
         #[cfg(feature = "reqwest_middleware")]
         let pulse_http = {
             let policy = ExponentialBackoff::builder()
                 .build_with_total_retry_duration(Duration::from_secs(10));
 
-            ClientBuilder::new(reqwest::Client::new())
+            client_builder()
                 .with(RetryTransientMiddleware::new_with_policy(policy))
                 .build()
         };
 
         #[cfg(not(feature = "reqwest_middleware"))]
-        let pulse_http = { ClientBuilder::new(reqwest::Client::new()).build() };
+        let pulse_http = { client_builder().build() };
 
         Self {
             config,
@@ -557,6 +592,15 @@ impl ServiceFactory {
         kafka::KafkaProducer::new(&self.config, topic)
     }
 
+    #[cfg(feature = "kafka")]
+    pub fn new_kafka_publisher_with_opts(
+        &self,
+        topic: &str,
+        opts: &kafka::KafkaProducerOpts,
+    ) -> Result<kafka::KafkaProducer> {
+        kafka::KafkaProducer::with_opts(&self.config, topic, opts)
+    }
+
     pub fn new_pulse_client(
         &self,
         workspace: WorkspaceUuid,
@@ -1,9 +1,33 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use derive_builder::Builder;
-use opentelemetry::{KeyValue, global};
+use opentelemetry::{
+    Context, KeyValue, global,
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::{TraceContextExt, TracerProvider},
+};
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_otlp::{
+    LogExporter, MetricExporter, Protocol, SpanExporter, WithExportConfig, WithHttpConfig,
+    WithTonicConfig, tonic_types::metadata::MetadataMap,
+};
 use opentelemetry_sdk::{
-    Resource, logs::SdkLoggerProvider, metrics::SdkMeterProvider, trace::SdkTracerProvider,
+    Resource,
+    logs::{BatchLogProcessor, SdkLoggerProvider},
+    metrics::{PeriodicReader, SdkMeterProvider},
+    propagation::TraceContextPropagator,
+    trace::{BatchSpanProcessor, Sampler, SdkTracerProvider},
 };
 use serde::Deserialize;
+use serde_with::{DurationSeconds, serde_as};
+use tracing::warn;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    Layer, filter::LevelFilter, filter::filter_fn, layer::SubscriberExt, util::SubscriberInitExt,
+};
+
+use crate::{Config, Error, Result};
 
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -14,12 +38,66 @@ pub enum OtelMode {
     Off,
 }
 
+/// OTLP transport, named as in `OTEL_EXPORTER_OTLP_PROTOCOL`
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OtelProtocol {
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    #[serde(rename = "grpc")]
+    Grpc,
+}
+
+#[serde_as]
 #[derive(Deserialize, Debug, Builder, Clone)]
 #[serde(rename_all = "lowercase")]
 pub struct OtelConfig {
     pub mode: OtelMode,
     pub service_name: String,
     pub service_version: String,
+
+    /// Collector base url, e.g. `http://localhost:4318` for HTTP or `http://localhost:4317` for gRPC.
+    /// By default it is taken from `OTEL_EXPORTER_OTLP_ENDPOINT` or the exporter's default
+    #[serde(default)]
+    #[builder(setter(strip_option, into), default)]
+    pub endpoint: Option<String>,
+
+    #[serde(default)]
+    #[builder(default)]
+    pub protocol: OtelProtocol,
+
+    /// Sent with every export, e.g. for authentication
+    #[serde(default)]
+    #[builder(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Fraction of new traces to sample, from 0 to 1
+    #[serde(default = "default_sampling_ratio")]
+    #[builder(default = "default_sampling_ratio()")]
+    pub sampling_ratio: f64,
+
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "default_metric_export_interval")]
+    #[builder(default = "default_metric_export_interval()")]
+    pub metric_export_interval: Duration,
+
+    /// How often batched spans and logs are exported
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "default_batch_export_interval")]
+    #[builder(default = "default_batch_export_interval()")]
+    pub batch_export_interval: Duration,
+}
+
+fn default_sampling_ratio() -> f64 {
+    1.0
+}
+
+fn default_metric_export_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_batch_export_interval() -> Duration {
+    Duration::from_secs(5)
 }
 
 fn resource(config: &OtelConfig) -> Resource {
@@ -32,91 +110,285 @@ fn resource(config: &OtelConfig) -> Resource {
         .build()
 }
 
-pub fn init(config: &OtelConfig) {
-    let resource = resource(config);
+fn metadata(headers: &HashMap<String, String>) -> Result<MetadataMap> {
+    let headers = headers
+        .iter()
+        .map(|(name, value)| {
+            Ok((
+                http::HeaderName::try_from(name).map_err(|_| Error::Other("InvalidOtelHeader"))?,
+                http::HeaderValue::try_from(value)
+                    .map_err(|_| Error::Other("InvalidOtelHeader"))?,
+            ))
+        })
+        .collect::<Result<http::HeaderMap>>()?;
+
+    Ok(MetadataMap::from_headers(headers))
+}
+
+/// Builds an OTLP exporter, `$path` is the signal's path for HTTP endpoints
+macro_rules! exporter {
+    ($builder:expr, $config:expr, $path:literal) => {
+        match $config.protocol {
+            OtelProtocol::HttpProtobuf => {
+                let mut builder = $builder
+                    .with_http()
+                    .with_protocol(Protocol::HttpBinary)
+                    .with_headers($config.headers.clone());
+
+                if let Some(endpoint) = &$config.endpoint {
+                    builder = builder.with_endpoint(format!(
+                        "{}/{}",
+                        endpoint.trim_end_matches('/'),
+                        $path
+                    ));
+                }
 
-    match config.mode {
+                builder.build()?
+            }
+            OtelProtocol::Grpc => {
+                let mut builder = $builder
+                    .with_tonic()
+                    .with_metadata(metadata(&$config.headers)?);
+
+                if let Some(endpoint) = &$config.endpoint {
+                    builder = builder.with_endpoint(endpoint.clone());
+                }
+
+                builder.build()?
+            }
+        }
+    };
+}
+
+pub fn meter_provider(config: &OtelConfig) -> Result<SdkMeterProvider> {
+    let builder = SdkMeterProvider::builder().with_resource(resource(config));
+
+    let builder = match config.mode {
+        OtelMode::On => builder.with_reader(
+            PeriodicReader::builder(exporter!(MetricExporter::builder(), config, "v1/metrics"))
+                .with_interval(config.metric_export_interval)
+                .build(),
+        ),
+        OtelMode::Stdout => builder.with_reader(
+            PeriodicReader::builder(opentelemetry_stdout::MetricExporterBuilder::default().build())
+                .with_interval(config.metric_export_interval)
+                .build(),
+        ),
+        OtelMode::Off => builder,
+    };
+
+    Ok(builder.build())
+}
+
+pub fn init(config: &OtelConfig) -> Result<()> {
+    global::set_meter_provider(meter_provider(config)?);
+    Ok(())
+}
+
+pub fn tracer_provider(config: &OtelConfig) -> Result<Option<SdkTracerProvider>> {
+    let batch_config = opentelemetry_sdk::trace::BatchConfigBuilder::default()
+        .with_scheduled_delay(config.batch_export_interval)
+        .build();
+
+    let processor = match config.mode {
         OtelMode::On => {
-            global::set_meter_provider(
-                SdkMeterProvider::builder()
-                    .with_periodic_exporter(
-                        opentelemetry_otlp::MetricExporter::builder()
-                            .with_http()
-                            .build()
-                            .unwrap(),
-                    )
-                    .with_resource(resource.clone())
-                    .build(),
-            );
+            BatchSpanProcessor::builder(exporter!(SpanExporter::builder(), config, "v1/traces"))
+                .with_batch_config(batch_config)
+                .build()
         }
         OtelMode::Stdout => {
-            global::set_meter_provider(
-                SdkMeterProvider::builder()
-                    .with_periodic_exporter(
-                        opentelemetry_stdout::MetricExporterBuilder::default().build(),
-                    )
-                    .with_resource(resource.clone())
-                    .build(),
-            );
+            BatchSpanProcessor::builder(opentelemetry_stdout::SpanExporter::default())
+                .with_batch_config(batch_config)
+                .build()
         }
-        OtelMode::Off => {
-            global::set_meter_provider(
-                SdkMeterProvider::builder()
-                    .with_resource(resource.clone())
-                    .build(),
-            );
+        OtelMode::Off => return Ok(None),
+    };
+
+    Ok(Some(
+        SdkTracerProvider::builder()
+            .with_span_processor(processor)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sampling_ratio,
+            ))))
+            .with_resource(resource(config))
+            .build(),
+    ))
+}
+
+pub fn logger_provider(config: &OtelConfig) -> Result<Option<SdkLoggerProvider>> {
+    let batch_config = opentelemetry_sdk::logs::BatchConfigBuilder::default()
+        .with_scheduled_delay(config.batch_export_interval)
+        .build();
+
+    let processor = match config.mode {
+        OtelMode::On => {
+            BatchLogProcessor::builder(exporter!(LogExporter::builder(), config, "v1/logs"))
+                .with_batch_config(batch_config)
+                .build()
+        }
+        OtelMode::Stdout => {
+            BatchLogProcessor::builder(opentelemetry_stdout::LogExporter::default())
+                .with_batch_config(batch_config)
+                .build()
+        }
+        OtelMode::Off => return Ok(None),
+    };
+
+    Ok(Some(
+        SdkLoggerProvider::builder()
+            .with_log_processor(processor)
+            .with_resource(resource(config))
+            .build(),
+    ))
+}
+
+/// Keeps telemetry running, flushes and shuts down the providers when dropped
+#[must_use = "telemetry is shut down when the guard is dropped"]
+pub struct TelemetryGuard {
+    meter: SdkMeterProvider,
+    tracer: Option<SdkTracerProvider>,
+    logger: Option<SdkLoggerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(tracer) = &self.tracer
+            && let Err(error) = tracer.force_flush().and_then(|_| tracer.shutdown())
+        {
+            warn!(%error, "failed to flush traces");
+        }
+
+        if let Err(error) = self.meter.force_flush().and_then(|_| self.meter.shutdown()) {
+            warn!(%error, "failed to flush metrics");
+        }
+
+        if let Some(logger) = &self.logger
+            && let Err(error) = logger.force_flush().and_then(|_| logger.shutdown())
+        {
+            warn!(%error, "failed to flush logs");
         }
     }
 }
 
-pub fn tracer_provider(config: &OtelConfig) -> Option<SdkTracerProvider> {
-    let resource = resource(config);
+/// Installs the global `tracing` subscriber at [`Config::log`] level, with OpenTelemetry
+/// metrics, traces and logs exported according to `otel`
+pub fn init_telemetry(config: &Config, otel: &OtelConfig) -> Result<TelemetryGuard> {
+    let meter = meter_provider(otel)?;
+    let tracer = tracer_provider(otel)?;
+    let logger = logger_provider(otel)?;
 
-    match config.mode {
-        OtelMode::On => Some(
-            SdkTracerProvider::builder()
-                .with_batch_exporter(
-                    opentelemetry_otlp::SpanExporter::builder()
-                        .with_http()
-                        .build()
-                        .unwrap(),
-                )
-                .with_resource(resource.clone())
-                .build(),
-        ),
-        OtelMode::Stdout => Some(
-            SdkTracerProvider::builder()
-                .with_batch_exporter(opentelemetry_stdout::SpanExporter::default())
-                .with_resource(resource.clone())
-                .build(),
-        ),
-        OtelMode::Off => None,
+    global::set_meter_provider(meter.clone());
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let trace_layer = tracer.as_ref().map(|provider| {
+        global::set_tracer_provider(provider.clone());
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(otel.service_name.clone()))
+    });
+
+    // Exporters log through `tracing` too, keep them out of the exported logs
+    let log_layer = logger.as_ref().map(|provider| {
+        OpenTelemetryTracingBridge::new(provider).with_filter(filter_fn(|metadata| {
+            !["opentelemetry", "hyper", "tonic", "h2", "reqwest"]
+                .iter()
+                .any(|target| metadata.target().starts_with(target))
+        }))
+    });
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::from_level(config.log))
+        .with(tracing_subscriber::fmt::layer())
+        .with(trace_layer)
+        .with(log_layer)
+        .try_init()
+        .map_err(|_| Error::Other("TelemetryAlreadyInitialized"))?;
+
+    Ok(TelemetryGuard {
+        meter,
+        tracer,
+        logger,
+    })
+}
+
+/// Context of the current `tracing` span, or the current OpenTelemetry context if the span isn't exported
+pub fn current_context() -> Context {
+    let context = tracing::Span::current().context();
+
+    if context.span().span_context().is_valid() {
+        context
+    } else {
+        Context::current()
     }
 }
 
-pub fn logger_provider(config: &OtelConfig) -> Option<SdkLoggerProvider> {
-    let resource = resource(config);
+/// Writes the current trace context as W3C `traceparent` and `tracestate`
+pub fn inject_trace_context(injector: &mut dyn Injector) {
+    TraceContextPropagator::new().inject_context(&current_context(), injector);
+}
 
-    match config.mode {
-        OtelMode::On => Some(
-            SdkLoggerProvider::builder()
-                .with_batch_exporter(
-                    opentelemetry_otlp::LogExporterBuilder::default()
-                        .with_http()
-                        .build()
-                        .unwrap(),
-                )
-                .with_resource(resource.clone())
-                .build(),
-        ),
+/// Current trace context as W3C `traceparent` and `tracestate` entries
+pub fn trace_context() -> HashMap<String, String> {
+    let mut entries = HashMap::new();
+    inject_trace_context(&mut entries);
+    entries
+}
 
-        OtelMode::Stdout => Some(
-            SdkLoggerProvider::builder()
-                .with_batch_exporter(opentelemetry_stdout::LogExporter::default())
-                .with_resource(resource.clone())
-                .build(),
-        ),
+/// Reads W3C `traceparent` and `tracestate`, e.g. from headers of an incoming message
+pub fn extract_trace_context(extractor: &dyn Extractor) -> Context {
+    TraceContextPropagator::new().extract(extractor)
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+    use super::*;
+
+    #[test]
+    fn test_trace_context_roundtrip() {
+        let span_context = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+
+        let _guard = Context::new()
+            .with_remote_span_context(span_context.clone())
+            .attach();
+
+        let entries = trace_context();
+        assert_eq!(
+            entries.get("traceparent").map(String::as_str),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+
+        let context = extract_trace_context(&entries);
+        assert_eq!(
+            context.span().span_context().trace_id(),
+            span_context.trace_id()
+        );
+        assert_eq!(
+            context.span().span_context().span_id(),
+            span_context.span_id()
+        );
+    }
+
+    #[test]
+    fn test_otel_config_defaults() {
+        let config: OtelConfig = serde_json::from_value(serde_json::json!({
+            "mode": "on",
+            "service_name": "test",
+            "service_version": "1.0",
+            "protocol": "grpc",
+            "batch_export_interval": 1,
+        }))
+        .unwrap();
 
-        OtelMode::Off => None,
+        assert_eq!(config.endpoint, None);
+        assert_eq!(config.protocol, OtelProtocol::Grpc);
+        assert_eq!(config.sampling_ratio, 1.0);
+        assert_eq!(config.metric_export_interval, Duration::from_secs(60));
+        assert_eq!(config.batch_export_interval, Duration::from_secs(1));
     }
 }
@@ -27,7 +27,7 @@ use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 use url::Url;
 
-use super::{ForceScheme, core::WorkspaceUuid};
+use super::{ForceScheme, core::WorkspaceUuid, metrics};
 
 pub struct PulseClient {
     token: SecretString,
@@ -133,6 +133,10 @@ impl PulseClient {
     }
 
     pub async fn list(&self, key_prefix: &str) -> Result<Vec<FullObject>> {
+        metrics::measure(metrics::service::PULSE, "list", self.list_inner(key_prefix)).await
+    }
+
+    async fn list_inner(&self, key_prefix: &str) -> Result<Vec<FullObject>> {
         let request = self.request(Method::GET, self.base.join(&format!("{key_prefix}/"))?);
         let response = request.send_ext().await?;
         let objects: Vec<ObjectResponse> = response.json().await?;
@@ -141,6 +145,10 @@ impl PulseClient {
     }
 
     pub async fn get(&self, key: &str) -> Result<Option<FullObject>> {
+        metrics::measure(metrics::service::PULSE, "get", self.get_inner(key)).await
+    }
+
+    async fn get_inner(&self, key: &str) -> Result<Option<FullObject>> {
         let request = self.request(Method::GET, self.base.join(key)?);
         let response = request.send().await?;
         if response.status() == StatusCode::NOT_FOUND {
@@ -162,6 +170,21 @@ impl PulseClient {
         data: String,
         expiration: Option<Expiration>,
         mode: PutMode,
+    ) -> Result<()> {
+        metrics::measure(
+            metrics::service::PULSE,
+            "put",
+            self.put_inner(key, data, expiration, mode),
+        )
+        .await
+    }
+
+    async fn put_inner(
+        &self,
+        key: &str,
+        data: String,
+        expiration: Option<Expiration>,
+        mode: PutMode,
     ) -> Result<()> {
         let request = self.request(Method::PUT, self.base.join(key)?);
 
@@ -183,6 +206,15 @@ impl PulseClient {
     }
 
     pub async fn delete(&self, key: &str, mode: PutMode) -> Result<()> {
+        metrics::measure(
+            metrics::service::PULSE,
+            "delete",
+            self.delete_inner(key, mode),
+        )
+        .await
+    }
+
+    async fn delete_inner(&self, key: &str, mode: PutMode) -> Result<()> {
         let request = self.request(Method::DELETE, self.base.join(key)?);
         let request = match mode {
             PutMode::Upsert => request,
@@ -0,0 +1,638 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! LexoRank compatible ranks, as in `@hcengineering/rank`
+//!
+//! Ranks have the form `<bucket>|<integer>:<fraction>`, where the integer part is
+//! padded to six base-36 digits, so they can be compared as plain strings.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use crate::services::core::classes::Rank;
+use crate::{Error, Result};
+
+const BASE: u8 = 36;
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const RADIX_POINT: char = ':';
+const INTEGER_WIDTH: usize = 6;
+
+fn to_digit(ch: u8) -> Option<u8> {
+    match ch {
+        b'0'..=b'9' => Some(ch - b'0'),
+        b'a'..=b'z' => Some(ch - b'a' + 10),
+        _ => None,
+    }
+}
+
+/// Non-negative base-36 integer, least significant digit first, without leading zeros
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Magnitude(Vec<u8>);
+
+impl Magnitude {
+    fn from_u64(mut value: u64) -> Self {
+        let mut digits = Vec::new();
+        while value > 0 {
+            digits.push((value % BASE as u64) as u8);
+            value /= BASE as u64;
+        }
+        Self(digits)
+    }
+
+    fn normalized(mut digits: Vec<u8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self(digits)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn digit(&self, index: usize) -> u8 {
+        self.0.get(index).copied().unwrap_or(0)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+
+        for i in 0..len {
+            let sum = self.digit(i) + other.digit(i) + carry;
+            digits.push(sum % BASE);
+            carry = sum / BASE;
+        }
+
+        if carry > 0 {
+            digits.push(carry);
+        }
+
+        Self::normalized(digits)
+    }
+
+    /// `self - other`, requires `self >= other`
+    fn sub(&self, other: &Self) -> Self {
+        let mut digits = Vec::with_capacity(self.0.len());
+        let mut borrow = 0;
+
+        for i in 0..self.0.len() {
+            let mut digit = self.digit(i) as i16 - other.digit(i) as i16 - borrow;
+            borrow = 0;
+            if digit < 0 {
+                digit += BASE as i16;
+                borrow = 1;
+            }
+            digits.push(digit as u8);
+        }
+
+        Self::normalized(digits)
+    }
+
+    fn mul_small(&self, factor: u8) -> Self {
+        let mut digits = Vec::with_capacity(self.0.len() + 1);
+        let mut carry = 0u16;
+
+        for digit in &self.0 {
+            let product = *digit as u16 * factor as u16 + carry;
+            digits.push((product % BASE as u16) as u8);
+            carry = product / BASE as u16;
+        }
+
+        while carry > 0 {
+            digits.push((carry % BASE as u16) as u8);
+            carry /= BASE as u16;
+        }
+
+        Self::normalized(digits)
+    }
+
+    fn shift_left(&self, count: usize) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        let mut digits = vec![0; count];
+        digits.extend_from_slice(&self.0);
+        Self(digits)
+    }
+
+    fn shift_right(&self, count: usize) -> Self {
+        Self::normalized(self.0.iter().skip(count).copied().collect())
+    }
+}
+
+impl Ord for Magnitude {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+impl PartialOrd for Magnitude {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Fixed point base-36 number, `mag / 36^scale`, without trailing fractional zeros
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Decimal {
+    mag: Magnitude,
+    scale: usize,
+}
+
+impl Decimal {
+    fn make(mag: Magnitude, scale: usize) -> Self {
+        if mag.is_zero() {
+            return Self { mag, scale: 0 };
+        }
+
+        let zeros = mag.0.iter().take(scale).take_while(|d| **d == 0).count();
+
+        Self {
+            mag: mag.shift_right(zeros),
+            scale: scale - zeros,
+        }
+    }
+
+    fn integer(value: u64) -> Self {
+        Self::make(Magnitude::from_u64(value), 0)
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let (integer, fraction) = s.split_once(RADIX_POINT).unwrap_or((s, ""));
+
+        let mut digits = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .map(to_digit)
+            .collect::<Option<Vec<_>>>()?;
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        digits.reverse();
+
+        Some(Self::make(Magnitude::normalized(digits), fraction.len()))
+    }
+
+    fn aligned(&self, other: &Self) -> (Magnitude, Magnitude) {
+        let scale = self.scale.max(other.scale);
+        (
+            self.mag.shift_left(scale - self.scale),
+            other.mag.shift_left(scale - other.scale),
+        )
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let (left, right) = self.aligned(other);
+        Self::make(left.add(&right), self.scale.max(other.scale))
+    }
+
+    fn half(&self) -> Self {
+        Self::make(self.mag.mul_small(BASE / 2), self.scale + 1)
+    }
+
+    fn set_scale(&self, scale: usize, ceiling: bool) -> Self {
+        if scale >= self.scale {
+            return self.clone();
+        }
+
+        let mut mag = self.mag.shift_right(self.scale - scale);
+        if ceiling {
+            mag = mag.add(&Magnitude::from_u64(1));
+        }
+
+        Self::make(mag, scale)
+    }
+
+    fn floor(&self) -> Self {
+        Self::make(self.mag.shift_right(self.scale), 0)
+    }
+
+    fn ceil(&self) -> Self {
+        if self.scale == 0 {
+            self.clone()
+        } else {
+            Self::make(self.floor().mag.add(&Magnitude::from_u64(1)), 0)
+        }
+    }
+
+    fn format(&self) -> String {
+        let mut digits = self
+            .mag
+            .0
+            .iter()
+            .rev()
+            .map(|d| DIGITS[*d as usize] as char)
+            .collect::<String>();
+
+        while digits.len() < self.scale + INTEGER_WIDTH {
+            digits.insert(0, '0');
+        }
+
+        digits.insert(digits.len() - self.scale, RADIX_POINT);
+        digits
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (left, right) = self.aligned(other);
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn min_decimal() -> Decimal {
+    Decimal::integer(0)
+}
+
+fn max_decimal() -> Decimal {
+    Decimal::integer(36u64.pow(INTEGER_WIDTH as u32) - 1)
+}
+
+fn mid(left: &Decimal, right: &Decimal) -> Decimal {
+    let mid = left.add(right).half();
+    let scale = left.scale.max(right.scale);
+
+    if mid.scale > scale {
+        let round_down = mid.set_scale(scale, false);
+        if &round_down > left {
+            return round_down;
+        }
+
+        let round_up = mid.set_scale(scale, true);
+        if &round_up < right {
+            return round_up;
+        }
+    }
+
+    mid
+}
+
+fn check_mid(lbound: &Decimal, rbound: &Decimal, candidate: Decimal) -> Decimal {
+    if lbound >= &candidate || &candidate >= rbound {
+        mid(lbound, rbound)
+    } else {
+        candidate
+    }
+}
+
+/// Shortest decimal strictly between `lbound` and `rbound`, requires `lbound < rbound`
+fn between(lbound: &Decimal, rbound: &Decimal) -> Decimal {
+    let mut left = lbound.clone();
+    let mut right = rbound.clone();
+
+    if lbound.scale < rbound.scale {
+        let rounded = rbound.set_scale(lbound.scale, false);
+        if lbound >= &rounded {
+            return mid(lbound, rbound);
+        }
+        right = rounded;
+    }
+
+    if lbound.scale > right.scale {
+        let rounded = lbound.set_scale(right.scale, true);
+        if rounded >= right {
+            return mid(lbound, rbound);
+        }
+        left = rounded;
+    }
+
+    let mut scale = left.scale;
+    while scale > 0 {
+        let next_left = left.set_scale(scale - 1, true);
+        let next_right = right.set_scale(scale - 1, false);
+
+        match next_left.cmp(&next_right) {
+            Ordering::Equal => return check_mid(lbound, rbound, next_left),
+            Ordering::Greater => break,
+            Ordering::Less => {}
+        }
+
+        scale -= 1;
+        left = next_left;
+        right = next_right;
+    }
+
+    let mut result = check_mid(lbound, rbound, mid(&left, &right));
+
+    while result.scale > 0 {
+        let shorter = result.set_scale(result.scale - 1, false);
+        if lbound >= &shorter || &shorter >= rbound {
+            break;
+        }
+        result = shorter;
+    }
+
+    result
+}
+
+/// A parsed LexoRank value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexoRank {
+    bucket: u8,
+    decimal: Decimal,
+}
+
+impl LexoRank {
+    pub fn parse(rank: &str) -> Result<Self> {
+        let (bucket, decimal) = rank.split_once('|').ok_or(Error::Other("InvalidRank"))?;
+
+        let bucket = match bucket {
+            "0" => 0,
+            "1" => 1,
+            "2" => 2,
+            _ => return Err(Error::Other("InvalidRankBucket")),
+        };
+
+        let decimal = Decimal::parse(decimal).ok_or(Error::Other("InvalidRank"))?;
+
+        Ok(Self { bucket, decimal })
+    }
+
+    pub fn min() -> Self {
+        Self {
+            bucket: 0,
+            decimal: min_decimal(),
+        }
+    }
+
+    pub fn max() -> Self {
+        Self {
+            bucket: 0,
+            decimal: max_decimal(),
+        }
+    }
+
+    pub fn middle() -> Self {
+        Self {
+            bucket: 0,
+            decimal: between(&min_decimal(), &max_decimal()),
+        }
+    }
+
+    pub fn bucket(&self) -> u8 {
+        self.bucket
+    }
+
+    /// Rank between `self` and `other`, in either order
+    pub fn between(&self, other: &Self) -> Result<Self> {
+        if self.bucket != other.bucket {
+            return Err(Error::Other("RankBucketMismatch"));
+        }
+
+        let decimal = match self.decimal.cmp(&other.decimal) {
+            Ordering::Less => between(&self.decimal, &other.decimal),
+            Ordering::Greater => between(&other.decimal, &self.decimal),
+            Ordering::Equal => return Err(Error::Other("SameRank")),
+        };
+
+        Ok(Self {
+            bucket: self.bucket,
+            decimal,
+        })
+    }
+
+    /// Rank after `self`, leaving room for further inserts
+    pub fn gen_next(&self) -> Self {
+        let decimal = if self.decimal == min_decimal() {
+            Decimal::integer(36u64.pow(INTEGER_WIDTH as u32 - 1))
+        } else {
+            let next = self.decimal.ceil().add(&Decimal::integer(8));
+            if next >= max_decimal() {
+                between(&self.decimal, &max_decimal())
+            } else {
+                next
+            }
+        };
+
+        Self {
+            bucket: self.bucket,
+            decimal,
+        }
+    }
+
+    /// Rank before `self`, leaving room for further inserts
+    pub fn gen_prev(&self) -> Self {
+        let decimal = if self.decimal == max_decimal() {
+            Decimal::integer(34 * 36u64.pow(INTEGER_WIDTH as u32 - 1))
+        } else {
+            let floor = self.decimal.floor();
+            let eight = Decimal::integer(8);
+            if floor <= eight {
+                between(&min_decimal(), &self.decimal)
+            } else {
+                Decimal::make(floor.mag.sub(&eight.mag), 0)
+            }
+        };
+
+        Self {
+            bucket: self.bucket,
+            decimal,
+        }
+    }
+}
+
+impl Display for LexoRank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.bucket, self.decimal.format())
+    }
+}
+
+impl PartialOrd for LexoRank {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LexoRank {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bucket
+            .cmp(&other.bucket)
+            .then_with(|| self.decimal.cmp(&other.decimal))
+    }
+}
+
+/// Makes a rank between `prev` and `next`, as `makeRank` in the platform.
+///
+/// When only one bound is given the rank is placed after `prev` or before `next`,
+/// when none is given the middle rank is returned.
+pub fn make_rank(prev: Option<&str>, next: Option<&str>) -> Result<Rank> {
+    let rank = match (prev, next) {
+        (Some(prev), Some(next)) => LexoRank::parse(prev)?.between(&LexoRank::parse(next)?)?,
+        (Some(prev), None) => LexoRank::parse(prev)?.gen_next(),
+        (None, Some(next)) => LexoRank::parse(next)?.gen_prev(),
+        (None, None) => LexoRank::middle(),
+    };
+
+    Ok(rank.to_string())
+}
+
+/// Generates `count` evenly spaced ranks, as `genRanks` in the platform
+pub fn gen_ranks(count: usize) -> Vec<Rank> {
+    let gap = 36u64.pow(INTEGER_WIDTH as u32) / (count as u64 + 2);
+
+    (1..=count as u64)
+        .map(|i| {
+            LexoRank {
+                bucket: 0,
+                decimal: Decimal::integer(gap * i),
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+/// Assigns evenly spaced ranks to `ranks`, keeping their relative order.
+///
+/// Returns the new rank for every input position; equal ranks keep their input order.
+pub fn rebalance<R: AsRef<str>>(ranks: &[R]) -> Vec<Rank> {
+    let mut order = (0..ranks.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| ranks[*a].as_ref().cmp(ranks[*b].as_ref()));
+
+    let mut result = vec![Rank::new(); ranks.len()];
+    for (index, rank) in order.into_iter().zip(gen_ranks(ranks.len())) {
+        result[index] = rank;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn is_platform_format(rank: &str) -> bool {
+        let Some((bucket, decimal)) = rank.split_once('|') else {
+            return false;
+        };
+        let Some((integer, fraction)) = decimal.split_once(':') else {
+            return false;
+        };
+
+        matches!(bucket, "0" | "1" | "2")
+            && integer.len() == 6
+            && integer.bytes().all(|c| to_digit(c).is_some())
+            && fraction.bytes().all(|c| to_digit(c).is_some())
+            && !fraction.ends_with('0')
+    }
+
+    fn rank_strategy() -> impl Strategy<Value = String> {
+        "[0-9a-y][0-9a-z]{5}(:[0-9a-z]{0,7}[1-9a-z])?".prop_map(|s| {
+            let s = if s.contains(':') { s } else { format!("{s}:") };
+            format!("0|{s}")
+        })
+    }
+
+    #[test]
+    fn test_known_ranks() {
+        assert_eq!(make_rank(None, None).unwrap(), "0|hzzzzz:");
+        assert_eq!(make_rank(Some("0|hzzzzz:"), None).unwrap(), "0|i00007:");
+        assert_eq!(make_rank(None, Some("0|hzzzzz:")).unwrap(), "0|hzzzzr:");
+        assert_eq!(
+            make_rank(Some("0|hzzzzz:"), Some("0|i00007:")).unwrap(),
+            "0|i00003:"
+        );
+        assert_eq!(
+            make_rank(Some("0|i00007:"), Some("0|hzzzzz:")).unwrap(),
+            "0|i00003:"
+        );
+        assert_eq!(
+            make_rank(Some("0|hzzzzz:"), Some("0|i00000:")).unwrap(),
+            "0|hzzzzz:i"
+        );
+        assert_eq!(make_rank(Some("0|000000:"), None).unwrap(), "0|100000:");
+        assert_eq!(make_rank(None, Some("0|zzzzzz:")).unwrap(), "0|y00000:");
+
+        assert!(make_rank(Some("0|hzzzzz:"), Some("0|hzzzzz:")).is_err());
+        assert!(make_rank(Some("0|hzzzzz:"), Some("1|hzzzzz:")).is_err());
+        assert!(make_rank(Some("hzzzzz"), None).is_err());
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let ranks = ["0|c:", "0|a:", "0|b:"];
+        let rebalanced = rebalance(&ranks);
+
+        assert!(rebalanced[1] < rebalanced[2]);
+        assert!(rebalanced[2] < rebalanced[0]);
+        assert!(rebalanced.iter().all(|r| is_platform_format(r)));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_between(a in rank_strategy(), b in rank_strategy()) {
+            prop_assume!(a != b);
+            let (low, high) = if a < b { (a, b) } else { (b, a) };
+
+            let rank = make_rank(Some(&low), Some(&high)).unwrap();
+
+            prop_assert!(is_platform_format(&rank), "{rank}");
+            prop_assert!(low < rank && rank < high, "{low} < {rank} < {high}");
+        }
+
+        #[test]
+        fn prop_next_prev(a in rank_strategy()) {
+            prop_assume!(a != "0|000000:");
+
+            let next = make_rank(Some(&a), None).unwrap();
+            let prev = make_rank(None, Some(&a)).unwrap();
+
+            prop_assert!(is_platform_format(&next) && is_platform_format(&prev));
+            prop_assert!(prev < a && a < next, "{prev} < {a} < {next}");
+        }
+
+        #[test]
+        fn prop_parse_roundtrip(a in rank_strategy()) {
+            prop_assert_eq!(LexoRank::parse(&a).unwrap().to_string(), a);
+        }
+
+        #[test]
+        fn prop_repeated_inserts(steps in proptest::collection::vec(any::<bool>(), 1..64)) {
+            let mut low = make_rank(None, None).unwrap();
+            let mut high = make_rank(Some(&low), None).unwrap();
+
+            for step in steps {
+                let rank = make_rank(Some(&low), Some(&high)).unwrap();
+                prop_assert!(low < rank && rank < high);
+
+                if step { low = rank } else { high = rank }
+            }
+        }
+
+        #[test]
+        fn prop_gen_ranks(count in 0usize..500) {
+            let ranks = gen_ranks(count);
+
+            prop_assert_eq!(ranks.len(), count);
+            prop_assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+            prop_assert!(ranks.iter().all(|r| is_platform_format(r)));
+        }
+    }
+}
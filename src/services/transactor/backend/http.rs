@@ -2,6 +2,7 @@ use crate::Result;
 use crate::services::core::WorkspaceUuid;
 use crate::services::core::classes::OperationDomain;
 use crate::services::core::storage::DomainResult;
+use crate::services::metrics::{self, service::TRANSACTOR_HTTP};
 use crate::services::transactor::backend::Backend;
 use crate::services::transactor::methods::Method;
 use crate::services::{JsonClient, TokenProvider};
@@ -111,9 +112,11 @@ impl super::Backend for HttpBackend {
         method: Method,
         params: impl IntoIterator<Item = (String, Value)>,
     ) -> Result<T> {
-        self.get_path(
-            &format!("/api/v1/{}/{}", method.kebab(), self.workspace()),
-            params,
+        let path = format!("/api/v1/{}/{}", method.kebab(), self.workspace());
+        metrics::measure(
+            TRANSACTOR_HTTP,
+            method.camel(),
+            self.get_path(&path, params),
         )
         .await
     }
@@ -123,11 +126,8 @@ impl super::Backend for HttpBackend {
         method: Method,
         body: &Q,
     ) -> Result<T> {
-        self.post_path(
-            &format!("/api/v1/{}/{}", method.kebab(), self.workspace()),
-            body,
-        )
-        .await
+        let path = format!("/api/v1/{}/{}", method.kebab(), self.workspace());
+        metrics::measure(TRANSACTOR_HTTP, method.camel(), self.post_path(&path, body)).await
     }
 
     async fn domain_request<T: DeserializeOwned + Send, Q: Serialize>(
@@ -137,20 +137,28 @@ impl super::Backend for HttpBackend {
         params: &Q,
     ) -> Result<DomainResult<T>> {
         let params = (String::from("params"), serde_json::to_value(params)?);
-        self.get_path(
-            &format!(
-                "/api/v1/{}/{domain}/{operation}/{}",
-                Method::Event.kebab(),
-                self.workspace()
-            ),
-            std::iter::once(params),
+        let path = format!(
+            "/api/v1/{}/{domain}/{operation}/{}",
+            Method::Event.kebab(),
+            self.workspace()
+        );
+
+        metrics::measure(
+            TRANSACTOR_HTTP,
+            operation,
+            self.get_path(&path, std::iter::once(params)),
         )
         .await
     }
 
     async fn tx_raw<T: Serialize, R: DeserializeOwned + Send>(&self, tx: T) -> Result<R> {
-        self.post_path(&format!("/api/v1/tx/{}", self.workspace()), &tx)
-            .await
+        let path = format!("/api/v1/tx/{}", self.workspace());
+        metrics::measure(
+            TRANSACTOR_HTTP,
+            Method::Tx.camel(),
+            self.post_path(&path, &tx),
+        )
+        .await
     }
 
     fn base(&self) -> &Url {
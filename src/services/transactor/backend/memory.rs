@@ -0,0 +1,741 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! In-memory [`Backend`] for unit tests, no transactor required
+
+use crate::services::TokenProvider;
+use crate::services::core::classes::OperationDomain;
+use crate::services::core::storage::DomainResult;
+use crate::services::core::{Account, WorkspaceUuid};
+use crate::services::core::{class, space};
+use crate::services::transactor::backend::{Backend, SubscriptionBackend};
+use crate::services::transactor::document::{Lookup, LookupValue, ReverseLookupValue};
+use crate::services::transactor::methods::Method;
+use crate::{Error, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+#[cfg(target_family = "wasm")]
+use tokio_with_wasm::alias as tokio;
+use url::Url;
+
+/// Transaction fields that are not document attributes
+const TX_FIELDS: &[&str] = &[
+    "_id",
+    "_class",
+    "space",
+    "modifiedOn",
+    "modifiedBy",
+    "createdOn",
+    "createdBy",
+    "objectId",
+    "objectClass",
+    "objectSpace",
+    "attachedTo",
+    "attachedToClass",
+    "collection",
+    "retrieve",
+];
+
+struct MemoryBackendInner {
+    workspace: WorkspaceUuid,
+    base: Url,
+    account: Mutex<Option<Account>>,
+    documents: Mutex<Vec<Value>>,
+    tx_broadcast: broadcast::Sender<Value>,
+}
+
+/// Keeps documents of a single workspace in memory.
+///
+/// Transactions sent with `tx_raw` are applied to the stored documents and broadcast to
+/// subscribers, `find_all` evaluates equality and basic `$` operators on the stored documents.
+///
+/// A query on a class also returns documents of its subclasses, as long as the class documents
+/// are stored with their `extends` attribute, e.g. a `MasterTag` extending `card:class:Card`.
+/// Without them only documents of exactly the queried class are returned.
+#[derive(Clone)]
+pub struct MemoryBackend {
+    inner: Arc<MemoryBackendInner>,
+}
+
+impl MemoryBackend {
+    pub fn new(workspace: WorkspaceUuid) -> Self {
+        let (tx_broadcast, _) = broadcast::channel(128);
+
+        Self {
+            inner: Arc::new(MemoryBackendInner {
+                workspace,
+                base: Url::parse("memory://transactor/").expect("valid url"),
+                account: Mutex::new(None),
+                documents: Mutex::new(Vec::new()),
+                tx_broadcast,
+            }),
+        }
+    }
+
+    /// Sets the account returned by `get_account`
+    pub fn set_account(&self, account: Account) {
+        *self.inner.account.lock().unwrap() = Some(account);
+    }
+
+    /// Stores a document as is, without broadcasting a transaction
+    pub fn insert<T: Serialize>(&self, doc: &T) -> Result<()> {
+        let doc = serde_json::to_value(doc)?;
+
+        if doc.get("_id").and_then(Value::as_str).is_none()
+            || doc.get("_class").and_then(Value::as_str).is_none()
+        {
+            return Err(Error::Other("DocumentWithoutIdOrClass"));
+        }
+
+        let mut documents = self.documents();
+        documents.retain(|existing| existing["_id"] != doc["_id"]);
+        documents.push(doc);
+
+        Ok(())
+    }
+
+    /// Sends a transaction to subscribers without applying it, e.g. a workspace event
+    pub fn broadcast(&self, tx: Value) {
+        let _ = self.inner.tx_broadcast.send(tx);
+    }
+
+    /// Snapshot of the stored documents, in insertion order
+    pub fn snapshot(&self) -> Vec<Value> {
+        self.documents().clone()
+    }
+
+    fn documents(&self) -> MutexGuard<'_, Vec<Value>> {
+        self.inner.documents.lock().unwrap()
+    }
+
+    fn apply(&self, tx: &Value) -> Result<()> {
+        let mut documents = self.documents();
+
+        let position = |documents: &[Value]| {
+            documents
+                .iter()
+                .position(|doc| doc["_id"] == tx["objectId"])
+        };
+
+        match tx["_class"].as_str() {
+            Some(class::TxCreateDoc) => {
+                if position(&documents).is_some() {
+                    return Err(Error::Other("DocumentAlreadyExists"));
+                }
+
+                let mut doc = tx["attributes"].as_object().cloned().unwrap_or_default();
+
+                doc.insert("_id".into(), tx["objectId"].clone());
+                doc.insert("_class".into(), tx["objectClass"].clone());
+                doc.insert("space".into(), tx["objectSpace"].clone());
+                doc.insert("modifiedOn".into(), tx["modifiedOn"].clone());
+                doc.insert("modifiedBy".into(), tx["modifiedBy"].clone());
+                doc.insert(
+                    "createdOn".into(),
+                    tx.get("createdOn").unwrap_or(&tx["modifiedOn"]).clone(),
+                );
+                doc.insert(
+                    "createdBy".into(),
+                    tx.get("createdBy").unwrap_or(&tx["modifiedBy"]).clone(),
+                );
+
+                for field in ["attachedTo", "attachedToClass", "collection"] {
+                    if let Some(value) = tx.get(field) {
+                        doc.insert(field.into(), value.clone());
+                    }
+                }
+
+                documents.push(Value::Object(doc));
+            }
+
+            Some(class::TxUpdateDoc) => {
+                let position = position(&documents).ok_or(Error::Other("DocumentNotFound"))?;
+                let doc = documents[position].as_object_mut().unwrap();
+
+                for (key, value) in tx.as_object().into_iter().flatten() {
+                    if !TX_FIELDS.contains(&key.as_str()) {
+                        update(doc, key, value)?;
+                    }
+                }
+
+                // `DocumentUpdate::space` overrides the space of the transaction itself
+                if let Some(target) = tx["space"].as_str().filter(|s| *s != space::Tx) {
+                    doc.insert("space".into(), target.into());
+                }
+
+                doc.insert("modifiedOn".into(), tx["modifiedOn"].clone());
+                doc.insert("modifiedBy".into(), tx["modifiedBy"].clone());
+            }
+
+            Some(class::TxRemoveDoc) => {
+                let position = position(&documents).ok_or(Error::Other("DocumentNotFound"))?;
+                documents.remove(position);
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn find_all(&self, class: &str, query: &Value, options: &Value) -> Result<Value> {
+        let documents = self.documents();
+
+        let mut value = Vec::new();
+        for doc in documents.iter() {
+            let is_instance = doc["_class"]
+                .as_str()
+                .is_some_and(|doc_class| is_subclass(&documents, doc_class, class));
+
+            if is_instance && matches(doc, query)? {
+                value.push(doc.clone());
+            }
+        }
+
+        let total = value.len();
+
+        if let Some(limit) = options.get("limit").and_then(Value::as_u64) {
+            value.truncate(limit as usize);
+        }
+
+        if let Some(lookup) = options.get("lookup").filter(|v| !v.is_null()) {
+            let lookup = serde_json::from_value::<Lookup>(lookup.clone())?;
+
+            for doc in value.iter_mut() {
+                lookup_doc(&documents, doc, &lookup);
+            }
+        }
+
+        if let Some(projection) = options.get("projection").and_then(Value::as_object) {
+            for doc in value.iter_mut() {
+                doc.as_object_mut().unwrap().retain(|key, _| {
+                    projection.contains_key(key) || key.starts_with('_') || key.starts_with('$')
+                });
+            }
+        }
+
+        Ok(json!({
+            "total": total,
+            "value": value,
+            "lookupMap": null,
+        }))
+    }
+}
+
+/// Applies a single field of `TxUpdateDoc` operations to `doc`
+fn update(doc: &mut Map<String, Value>, key: &str, value: &Value) -> Result<()> {
+    let fields = || {
+        value
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(field, value)| (field.as_str(), value))
+    };
+
+    match key.strip_prefix('$').unwrap_or(key) {
+        "inc" => {
+            for (field, by) in fields() {
+                let current = doc.get(field).and_then(Value::as_f64).unwrap_or_default();
+                let by = by.as_f64().ok_or(Error::Other("IncrementIsNotNumber"))?;
+                let sum = current + by;
+
+                let sum =
+                    if sum.fract() == 0.0 && (i64::MIN as f64..=i64::MAX as f64).contains(&sum) {
+                        json!(sum as i64)
+                    } else {
+                        json!(sum)
+                    };
+
+                doc.insert(field.to_owned(), sum);
+            }
+        }
+
+        "unset" => {
+            for (field, _) in fields() {
+                doc.remove(field);
+            }
+        }
+
+        "push" => {
+            for (field, item) in fields() {
+                let array = doc
+                    .entry(field)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .ok_or(Error::Other("PushToNonArray"))?;
+
+                match item.get("$each").and_then(Value::as_array) {
+                    Some(items) => array.extend(items.iter().cloned()),
+                    None => array.push(item.clone()),
+                }
+            }
+        }
+
+        "pull" => {
+            for (field, item) in fields() {
+                if let Some(array) = doc.get_mut(field).and_then(Value::as_array_mut) {
+                    match item.get("$in").and_then(Value::as_array) {
+                        Some(items) => array.retain(|v| !items.contains(v)),
+                        None => array.retain(|v| v != item),
+                    }
+                }
+            }
+        }
+
+        "update" => return Err(Error::Other("UnsupportedUpdateOperator")),
+
+        _ if key.starts_with('$') => return Err(Error::Other("UnsupportedUpdateOperator")),
+
+        _ => {
+            doc.insert(key.to_owned(), value.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `class` is `base` or extends it, following `extends` of the stored class documents
+fn is_subclass(documents: &[Value], class: &str, base: &str) -> bool {
+    let mut class = class;
+
+    // bounded, so that a cyclic hierarchy cannot hang the query
+    for _ in 0..documents.len() + 1 {
+        if class == base {
+            return true;
+        }
+
+        let extends = documents
+            .iter()
+            .find(|doc| doc["_id"].as_str() == Some(class))
+            .and_then(|doc| doc["extends"].as_str());
+
+        match extends {
+            Some(extends) => class = extends,
+            None => return false,
+        }
+    }
+
+    false
+}
+
+/// Value of a dotted `path` in `doc`, a key applied to an array is collected from its items
+fn field<'a>(doc: &'a Value, path: &str) -> Option<Cow<'a, Value>> {
+    path.split('.').try_fold(Cow::Borrowed(doc), |value, key| {
+        let collect = |items: &[Value]| {
+            Value::Array(
+                items
+                    .iter()
+                    .filter_map(|item| item.get(key).cloned())
+                    .collect(),
+            )
+        };
+
+        Some(match value {
+            Cow::Borrowed(Value::Array(items)) => Cow::Owned(collect(items)),
+            Cow::Borrowed(value) => Cow::Borrowed(value.get(key)?),
+            Cow::Owned(Value::Array(items)) => Cow::Owned(collect(&items)),
+            Cow::Owned(value) => Cow::Owned(value.get(key)?.clone()),
+        })
+    })
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// Equality, where an array field matches if any of its items is equal
+fn equals(value: Option<&Value>, expected: &Value) -> bool {
+    match value {
+        Some(Value::Array(items)) if !expected.is_array() => items.contains(expected),
+        Some(value) => value == expected,
+        None => expected.is_null(),
+    }
+}
+
+fn matches(doc: &Value, query: &Value) -> Result<bool> {
+    for (path, expected) in query.as_object().into_iter().flatten() {
+        let value = field(doc, path);
+        let value = value.as_deref();
+
+        let operators = expected
+            .as_object()
+            .filter(|object| object.keys().any(|key| key.starts_with('$')));
+
+        let Some(operators) = operators else {
+            if !equals(value, expected) {
+                return Ok(false);
+            }
+
+            continue;
+        };
+
+        for (operator, operand) in operators {
+            let ordered = |accept: fn(Ordering) -> bool| {
+                value
+                    .and_then(|value| compare(value, operand))
+                    .is_some_and(accept)
+            };
+
+            let matched = match operator.as_str() {
+                "$in" => operand
+                    .as_array()
+                    .is_some_and(|items| items.iter().any(|item| equals(value, item))),
+                "$nin" => operand
+                    .as_array()
+                    .is_none_or(|items| !items.iter().any(|item| equals(value, item))),
+                "$ne" => !equals(value, operand),
+                "$gt" => ordered(Ordering::is_gt),
+                "$gte" => ordered(Ordering::is_ge),
+                "$lt" => ordered(Ordering::is_lt),
+                "$lte" => ordered(Ordering::is_le),
+                "$exists" => {
+                    value.is_some_and(|v| !v.is_null()) == operand.as_bool().unwrap_or(true)
+                }
+                _ => return Err(Error::Other("UnsupportedQueryOperator")),
+            };
+
+            if !matched {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Fills `$lookup` of `doc` with the referenced documents inline
+fn lookup_doc(documents: &[Value], doc: &mut Value, lookup: &Lookup) {
+    let mut lookups = Map::new();
+
+    for (key, value) in &lookup.forward_lookups {
+        let (class, nested) = match value {
+            LookupValue::Simple(class) => (class, None),
+            LookupValue::Nested(class, nested) => (class, Some(nested)),
+        };
+
+        let found = field(doc, key).and_then(|id| {
+            documents
+                .iter()
+                .find(|d| d["_id"] == *id && d["_class"].as_str() == Some(class))
+        });
+
+        let found = match (found, nested) {
+            (Some(found), Some(nested)) => {
+                let mut found = found.clone();
+                lookup_doc(documents, &mut found, nested);
+                found
+            }
+            (Some(found), None) => found.clone(),
+            (None, _) => Value::Null,
+        };
+
+        lookups.insert(key.clone(), found);
+    }
+
+    for (key, value) in lookup.reverse_lookups.iter().flatten() {
+        let (class, attribute) = match value {
+            ReverseLookupValue::Simple(class) => (class, "attachedTo"),
+            ReverseLookupValue::WithAttribute(class, attribute) => (class, attribute.as_str()),
+        };
+
+        let found = documents
+            .iter()
+            .filter(|d| d["_class"].as_str() == Some(class) && d.get(attribute) == doc.get("_id"))
+            .cloned()
+            .collect();
+
+        lookups.insert(key.clone(), Value::Array(found));
+    }
+
+    doc["$lookup"] = Value::Object(lookups);
+}
+
+impl TokenProvider for MemoryBackend {
+    fn provide_token(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Backend for MemoryBackend {
+    async fn get<T: DeserializeOwned + Send>(
+        &self,
+        method: Method,
+        params: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<T> {
+        match method {
+            Method::Account => {
+                let account = self.inner.account.lock().unwrap().clone();
+                let account = account.ok_or(Error::Other("AccountNotSet"))?;
+                Ok(serde_json::from_value(serde_json::to_value(account)?)?)
+            }
+
+            Method::FindAll => {
+                let (mut class, mut query, mut options) = (Value::Null, Value::Null, Value::Null);
+
+                for (name, value) in params {
+                    match name.as_str() {
+                        "class" => class = value,
+                        "query" => query = value,
+                        "options" => options = value,
+                        _ => {}
+                    }
+                }
+
+                let class = class.as_str().ok_or(Error::Other("ClassIsNotString"))?;
+                Ok(serde_json::from_value(
+                    self.find_all(class, &query, &options)?,
+                )?)
+            }
+
+            _ => Err(Error::Other("UnsupportedMethod")),
+        }
+    }
+
+    async fn post<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        method: Method,
+        body: &Q,
+    ) -> Result<T> {
+        match method {
+            Method::Tx => self.tx_raw(body).await,
+            _ => Err(Error::Other("UnsupportedMethod")),
+        }
+    }
+
+    async fn domain_request<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        _domain: OperationDomain,
+        _operation: &str,
+        _params: &Q,
+    ) -> Result<DomainResult<T>> {
+        Err(Error::Other("UnsupportedMethod"))
+    }
+
+    async fn tx_raw<T: Serialize, R: DeserializeOwned + Send>(&self, tx: T) -> Result<R> {
+        let tx = serde_json::to_value(tx)?;
+
+        self.apply(&tx)?;
+        let _ = self.inner.tx_broadcast.send(tx);
+
+        Ok(serde_json::from_value(json!({}))?)
+    }
+
+    fn base(&self) -> &Url {
+        &self.inner.base
+    }
+
+    fn workspace(&self) -> WorkspaceUuid {
+        self.inner.workspace
+    }
+}
+
+impl SubscriptionBackend for MemoryBackend {
+    fn tx_stream(&self) -> BroadcastStream<Value> {
+        self.inner.tx_broadcast.subscribe().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::lookup;
+    use crate::services::event::Class;
+    use crate::services::transactor::document::{
+        CreateDocument, DocumentClient, FindOptions, RemoveDocument, UpdateDocument,
+    };
+    use crate::services::transactor::subscription::{LiveQueryEvent, TxEvent};
+    use crate::services::transactor::{Transaction, TransactorClient};
+    use crate::testing::test_support::{Issue, issue};
+
+    fn create(id: &str, title: &str, estimation: i64) -> CreateDocument<Value> {
+        issue(id, json!({ "title": title, "estimation": estimation }))
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend() {
+        let backend = MemoryBackend::new(WorkspaceUuid::nil());
+        let client = TransactorClient::new_memory(backend.clone());
+
+        backend
+            .insert(&json!({ "_id": "p1", "_class": "tracker:class:Project", "name": "Huly" }))
+            .unwrap();
+
+        let mut live = Box::pin(client.live_query::<Issue, _>(json!({}), FindOptions::default()));
+        let Some(Ok(LiveQueryEvent::Initial(initial))) = live.next().await else {
+            panic!("expected initial results");
+        };
+        assert!(initial.is_empty());
+
+        client
+            .tx::<_, Value>(create("i1", "First", 1))
+            .await
+            .unwrap();
+        client
+            .tx::<_, Value>(create("i2", "Second", 2))
+            .await
+            .unwrap();
+
+        let Some(Ok(LiveQueryEvent::Polled(TxEvent::Created(tx)))) = live.next().await else {
+            panic!("expected a created event");
+        };
+        assert_eq!(tx.txcud.object_id, "i1");
+
+        let update = UpdateDocument::builder()
+            .object_id("i2")
+            .object_class(Issue::CLASS)
+            .object_space("space")
+            .set("project", "p1")
+            .build()
+            .unwrap();
+        let mut update = update.to_value().unwrap();
+        update["$inc"] = json!({ "estimation": 3 });
+        client.tx_raw::<_, Value>(update).await.unwrap();
+
+        let found = client
+            .find_all::<_, Issue>(
+                Issue::CLASS,
+                json!({ "estimation": { "$gt": 1 } }),
+                &FindOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.total, 1);
+        assert_eq!(found.value[0].estimation, 5);
+
+        let found = client
+            .find_all::<_, Value>(
+                Issue::CLASS,
+                json!({}),
+                &FindOptions::builder()
+                    .limit(1)
+                    .project("title")
+                    .lookup(lookup! { project: "tracker:class:Project" })
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.total, 2);
+        assert_eq!(found.value.len(), 1);
+        assert_eq!(found.value[0].get("estimation"), None);
+
+        let found = client
+            .find_one::<_, Value>(
+                Issue::CLASS,
+                json!({ "_id": "i2" }),
+                &FindOptions::builder()
+                    .lookup(lookup! { project: "tracker:class:Project" })
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found["$lookup"]["project"]["name"], "Huly");
+
+        let remove = RemoveDocument::builder()
+            .object_id("i1")
+            .object_class(Issue::CLASS)
+            .object_space("space")
+            .build()
+            .unwrap();
+        client.tx::<_, Value>(remove).await.unwrap();
+        assert_eq!(backend.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_update_space_and_subclass() {
+        let backend = MemoryBackend::new(WorkspaceUuid::nil());
+        let client = TransactorClient::new_memory(backend.clone());
+
+        backend
+            .insert(&json!({ "_id": "tracker:class:Bug", "_class": "core:class:Class", "extends": Issue::CLASS }))
+            .unwrap();
+        client
+            .tx::<_, Value>(create("i1", "First", 1))
+            .await
+            .unwrap();
+
+        let mut bug = create("b1", "Bug", 2).to_value().unwrap();
+        bug["objectClass"] = json!("tracker:class:Bug");
+        client.tx_raw::<_, Value>(bug).await.unwrap();
+
+        let found = client
+            .find_all::<_, Issue>(Issue::CLASS, json!({}), &FindOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(found.total, 2);
+
+        let moved = UpdateDocument::builder()
+            .object_id("i1")
+            .object_class(Issue::CLASS)
+            .object_space("space")
+            .build()
+            .unwrap();
+        let mut moved = moved.to_value().unwrap();
+        moved["space"] = json!("archive");
+        client.tx_raw::<_, Value>(moved.clone()).await.unwrap();
+
+        let found = client
+            .find_one::<_, Value>(
+                Issue::CLASS,
+                json!({ "_id": "i1" }),
+                &FindOptions::default(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found["space"], "archive");
+
+        moved["update"] = json!({ "title": "Second" });
+        assert!(client.tx_raw::<_, Value>(moved).await.is_err());
+    }
+
+    #[test]
+    fn test_array_path_query() {
+        let backend = MemoryBackend::new(WorkspaceUuid::nil());
+
+        backend
+            .insert(&json!({
+                "_id": "c2",
+                "_class": "card:class:Card",
+                "parentInfo": [{ "_id": "c0" }, { "_id": "c1" }],
+            }))
+            .unwrap();
+
+        let found = |query| {
+            backend
+                .find_all("card:class:Card", &query, &json!({}))
+                .unwrap()
+        };
+
+        assert_eq!(found(json!({ "parentInfo._id": "c1" }))["total"], 1);
+        assert_eq!(
+            found(json!({ "parentInfo._id": { "$in": ["c0"] } }))["total"],
+            1
+        );
+        assert_eq!(found(json!({ "parentInfo._id": "c2" }))["total"], 0);
+    }
+}
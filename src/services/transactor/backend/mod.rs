@@ -8,9 +8,12 @@ use crate::services::transactor::methods::Method;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
+use tokio_stream::wrappers::BroadcastStream;
 use url::Url;
 
 pub mod http;
+#[cfg(feature = "testing")]
+pub mod memory;
 pub mod ws;
 
 #[allow(async_fn_in_trait)]
@@ -44,3 +47,8 @@ pub trait Backend: Clone + TokenProvider + 'static {
 
     fn workspace(&self) -> WorkspaceUuid;
 }
+
+/// Backend that receives transactions broadcast by the transactor, used by subscriptions
+pub trait SubscriptionBackend: Backend {
+    fn tx_stream(&self) -> BroadcastStream<Value>;
+}
@@ -1,11 +1,14 @@
+use crate::services::TokenProvider;
 use crate::services::core::WorkspaceUuid;
+use crate::services::core::class;
 use crate::services::core::classes::OperationDomain;
 use crate::services::core::storage::DomainResult;
+use crate::services::core::tx::WorkspaceEvent;
+use crate::services::metrics::{self, service::TRANSACTOR_WS};
 use crate::services::rpc::util::OkResponse;
 use crate::services::rpc::{HelloRequest, HelloResponse, ReqId, Request, Response};
-use crate::services::transactor::backend::Backend;
+use crate::services::transactor::backend::{Backend, SubscriptionBackend};
 use crate::services::transactor::methods::Method;
-use crate::services::{Status, TokenProvider};
 use crate::{Error, Result};
 use bytes::Bytes;
 use futures::stream::{SplitSink, SplitStream};
@@ -38,33 +41,99 @@ use {std::time::Instant, tokio::time::sleep, tokio::time::timeout};
 const PING: &str = "ping";
 const PONG: &str = "pong!";
 
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
 enum Command {
     Call {
         payload: Value,
-        reply_tx: oneshot::Sender<std::result::Result<OkResponse<Value>, Status>>,
+        reply_tx: CallReply,
     },
     Ping {
-        reply_tx: oneshot::Sender<std::result::Result<(), Status>>,
+        reply_tx: oneshot::Sender<Result<()>>,
     },
-    // TODO: Manual close
-    #[allow(dead_code)]
     Close,
 }
 
+type CallReply = oneshot::Sender<Result<OkResponse<Value>>>;
+
+struct PendingCall {
+    payload: Value,
+    reply_tx: CallReply,
+    /// Written to a socket, so the server may have acted on it already
+    sent: bool,
+}
+
+impl PendingCall {
+    /// Whether the call can be repeated without side effects
+    fn is_read(&self) -> bool {
+        matches!(
+            self.payload["method"].as_str().and_then(Method::from_camel),
+            Some(Method::FindAll | Method::Account)
+        )
+    }
+}
+
+/// Connection state which outlives a single socket, so that calls survive a reconnect
+struct SocketState {
+    pending: HashMap<ReqId, PendingCall>,
+    pending_ping: Option<oneshot::Sender<Result<()>>>,
+    next_id: AtomicI32,
+    hello_tx: Option<oneshot::Sender<Result<()>>>,
+    /// When the server announced an upgrade or maintenance, calls are held back until the next HELLO
+    paused: Option<Instant>,
+    /// Wait before the next reconnect attempt, reset once a socket is greeted with HELLO
+    reconnect_delay: Duration,
+    /// When the first reconnect attempt since the last HELLO was made
+    reconnecting_since: Option<Instant>,
+}
+
+impl SocketState {
+    fn new(hello_tx: oneshot::Sender<Result<()>>) -> Self {
+        Self {
+            pending: HashMap::new(),
+            pending_ping: None,
+            next_id: AtomicI32::new(1),
+            hello_tx: Some(hello_tx),
+            paused: None,
+            reconnect_delay: MIN_RECONNECT_DELAY,
+            reconnecting_since: None,
+        }
+    }
+
+    /// Replies to every call and ping left unanswered with `error`
+    fn fail_pending(&mut self, error: fn() -> Error) {
+        for (_, call) in self.pending.drain() {
+            let _ = call.reply_tx.send(Err(error()));
+        }
+
+        if let Some(pong_tx) = self.pending_ping.take() {
+            let _ = pong_tx.send(Err(error()));
+        }
+    }
+}
+
+fn is_upgrade_event(tx: &Value) -> bool {
+    tx.get("_class").and_then(Value::as_str) == Some(class::TxWorkspaceEvent)
+        && serde_json::from_value::<WorkspaceEvent>(tx["event"].clone()).is_ok_and(|event| {
+            matches!(
+                event,
+                WorkspaceEvent::UpgradeScheduled | WorkspaceEvent::MaintenanceNotification
+            )
+        })
+}
+
 async fn socket_task(
     mut write: SplitSink<WebSocket, Message>,
     mut read: SplitStream<WebSocket>,
-    mut cmd_rx: mpsc::UnboundedReceiver<Command>,
+    cmd_rx: &mut mpsc::UnboundedReceiver<Command>,
+    state: &mut SocketState,
     opts: WsBackendOpts,
-    hello_tx: oneshot::Sender<Result<()>>,
-    tx_broadcast: broadcast::Sender<Value>,
+    tx_broadcast: &broadcast::Sender<Value>,
 ) -> Result<()> {
-    let mut pending_ping = None;
-    let mut pending =
-        HashMap::<ReqId, oneshot::Sender<std::result::Result<OkResponse<Value>, Status>>>::new();
     let mut binary_mode = opts.binary;
     let mut use_compression = opts.compression;
-    let next_id = AtomicI32::new(1);
+    let mut ready = false;
 
     let hello = HelloRequest {
         request: Request {
@@ -79,34 +148,54 @@ async fn socket_task(
     trace!(target: "ws", ?hello, "sending HELLO");
     write.send(encode_message(&hello, binary_mode)?).await?;
 
-    let mut hello_tx = Some(hello_tx);
+    let hello_deadline = sleep(opts.hello_timeout);
+    tokio::pin!(hello_deadline);
+
     loop {
         tokio::select! {
-            Some(cmd) = cmd_rx.recv() => match cmd {
+            _ = &mut hello_deadline, if !ready => {
+                if let Some(hello_tx) = state.hello_tx.take() {
+                    let _ = hello_tx.send(Err(Error::Other("timed out waiting for HELLO")));
+                }
+                return Err(Error::Other("timed out waiting for HELLO"));
+            },
+
+            _ = sleep(pause_left(state, opts)), if ready && state.paused.is_some() => {
+                warn!(target: "ws", "Server kept the socket open after announcing an upgrade, reconnecting");
+                break;
+            },
+
+            Some(cmd) = cmd_rx.recv(), if ready => match cmd {
                 Command::Call { mut payload, reply_tx } => {
-                    let id = next_id.fetch_add(1, Ordering::Relaxed);
+                    let id = state.next_id.fetch_add(1, Ordering::Relaxed);
                     payload["id"] = Value::Number(id.into());
 
-                    trace!(target: "ws", %payload, "Sending message");
+                    // While paused the call is only sent to the next socket
+                    let sent = state.paused.is_none();
+                    if sent {
+                        trace!(target: "ws", %payload, "Sending message");
+                        write.send(encode_message(&payload, binary_mode)?).await?;
+                    }
 
-                    pending.insert(id.into(), reply_tx);
-                    write.send(encode_message(&payload, binary_mode)?).await?;
+                    state.pending.insert(id.into(), PendingCall { payload, reply_tx, sent });
                 },
                 Command::Ping { reply_tx } => {
-                    let payload = Request {
-                        id: None,
-                        method: Method::Ping.camel().to_string(),
-                        params: Vec::<()>::new(),
-                        time: None,
-                    };
-
-                    write.send(encode_message(&payload, binary_mode)?).await?;
-                    pending_ping = Some(reply_tx);
+                    write.send(encode_message(&ping_request(), binary_mode)?).await?;
+                    state.pending_ping = Some(reply_tx);
+                },
+                Command::Close => {
+                    // Closed on purpose, there is nothing to reconnect to
+                    state.paused = None;
+                    let _ = write.close().await;
+                    break;
                 },
-                Command::Close => break,
             },
 
-            Some(message) = read.next() => {
+            message = read.next() => {
+                let Some(message) = message else {
+                    break;
+                };
+
                 trace!(target: "ws", ?message, "Got message");
 
                 let response: Response<Value>;
@@ -160,7 +249,7 @@ async fn socket_task(
                 }
 
                 if response.result.as_ref().is_some_and(|v| v == PONG) {
-                    if let Some(pong_tx) = pending_ping.take() {
+                    if let Some(pong_tx) = state.pending_ping.take() {
                         let _ = pong_tx.send(Ok(()));
                     }
 
@@ -171,14 +260,23 @@ async fn socket_task(
                     if response.result.is_none() && response.error.is_some() {
                         let result = response.into_result();
                         error!(target: "ws", ?result);
+
+                        if !ready {
+                            let status = result.expect_err("error response");
+                            if let Some(hello_tx) = state.hello_tx.take() {
+                                let _ = hello_tx.send(Err(Error::ServiceError(status.clone())));
+                            }
+                            return Err(Error::ServiceError(status));
+                        }
+
                         continue;
                     }
 
                     if response.result.is_some_and(|result| result == "hello") {
                         // Just ignore any extra HELLOs
-                        let Some(hello_tx) = hello_tx.take() else {
+                        if ready {
                             continue;
-                        };
+                        }
 
                         let hello = serde_json::from_slice::<HelloResponse>(&payload)?;
                         binary_mode = hello.binary;
@@ -189,7 +287,38 @@ async fn socket_task(
                             use_compression = hello.use_compression.unwrap_or(false);
                         }
 
-                        let _ = hello_tx.send(Ok(()));
+                        ready = true;
+                        state.paused = None;
+                        state.reconnect_delay = MIN_RECONNECT_DELAY;
+                        state.reconnecting_since = None;
+
+                        if let Some(hello_tx) = state.hello_tx.take() {
+                            let _ = hello_tx.send(Ok(()));
+                        }
+
+                        // The previous connection may have applied what it was sent, only reads are repeated
+                        let interrupted = state
+                            .pending
+                            .iter()
+                            .filter(|(_, call)| call.sent && !call.is_read())
+                            .map(|(id, _)| id.clone())
+                            .collect::<Vec<_>>();
+
+                        for id in interrupted {
+                            if let Some(call) = state.pending.remove(&id) {
+                                let _ = call.reply_tx.send(Err(Error::CallInterrupted));
+                            }
+                        }
+
+                        for call in state.pending.values_mut() {
+                            write.send(encode_message(&call.payload, binary_mode)?).await?;
+                            call.sent = true;
+                        }
+
+                        if state.pending_ping.is_some() {
+                            write.send(encode_message(&ping_request(), binary_mode)?).await?;
+                        }
+
                         continue;
                     }
 
@@ -198,8 +327,8 @@ async fn socket_task(
 
                 trace!(target: "ws", ?response, "Full response");
                 if let Some(id) = &response.id
-                    && let Some(tx) = pending.remove(id) {
-                        let _ = tx.send(response.into_result()).ok();
+                    && let Some(call) = state.pending.remove(id) {
+                        let _ = call.reply_tx.send(response.into_result().map_err(Error::from)).ok();
                         continue;
                     }
 
@@ -208,6 +337,14 @@ async fn socket_task(
                     match serde_json::from_value::<Vec<Value>>(result) {
                         Ok(tx_array) => {
                             for tx in tx_array {
+                                if opts.upgrade_policy == UpgradePolicy::PauseAndReconnect
+                                    && state.paused.is_none()
+                                    && is_upgrade_event(&tx)
+                                {
+                                    warn!(target: "ws", "Workspace upgrade announced, pausing until reconnect");
+                                    state.paused = Some(Instant::now());
+                                }
+
                                 let _ = tx_broadcast.send(tx);
                             }
                         }
@@ -223,6 +360,85 @@ async fn socket_task(
     Ok(())
 }
 
+/// Time left until a paused connection gives up waiting for the server to close the socket
+fn pause_left(state: &SocketState, opts: WsBackendOpts) -> Duration {
+    state
+        .paused
+        .map(|since| opts.pause_timeout.saturating_sub(since.elapsed()))
+        .unwrap_or_default()
+}
+
+fn ping_request() -> Request<()> {
+    Request {
+        id: None,
+        method: Method::Ping.camel().to_string(),
+        params: Vec::new(),
+        time: None,
+    }
+}
+
+async fn open_socket(base: &Url, token: &SecretString) -> Result<WebSocket> {
+    let url = base.join(token.expose_secret())?;
+    let resp = Client::default()
+        .get(url)
+        .bearer_auth(token.expose_secret())
+        .upgrade()
+        .send()
+        .await?;
+
+    Ok(resp.into_websocket().await?)
+}
+
+/// Runs the socket, reconnecting after an announced upgrade if [`UpgradePolicy::PauseAndReconnect`] is set
+async fn connection_task(
+    ws: WebSocket,
+    base: Url,
+    token: SecretString,
+    mut cmd_rx: mpsc::UnboundedReceiver<Command>,
+    mut state: SocketState,
+    opts: WsBackendOpts,
+    tx_broadcast: broadcast::Sender<Value>,
+) -> Result<()> {
+    let mut ws = ws;
+
+    loop {
+        let (write, read) = ws.split();
+        let result = socket_task(write, read, &mut cmd_rx, &mut state, opts, &tx_broadcast).await;
+
+        // Sending stays paused until the new socket is greeted with HELLO
+        if state.paused.is_none() {
+            return result;
+        }
+
+        if let Err(e) = result {
+            warn!(target: "ws", ?e, "socket closed during upgrade");
+        }
+
+        let since = *state.reconnecting_since.get_or_insert_with(Instant::now);
+
+        ws = loop {
+            if since.elapsed() >= opts.reconnect_timeout {
+                error!(target: "ws", "Could not reconnect in time, giving up");
+                state.fail_pending(|| Error::ReconnectTimeout);
+                return Err(Error::ReconnectTimeout);
+            }
+
+            let delay = state.reconnect_delay;
+            trace!(target: "ws", ?delay, "Reconnecting");
+            sleep(delay).await;
+            state.reconnect_delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+
+            let result = open_socket(&base, &token).await;
+            metrics::ws_reconnect(&result);
+
+            match result {
+                Ok(ws) => break ws,
+                Err(e) => warn!(target: "ws", ?e, "reconnect failed"),
+            }
+        };
+    }
+}
+
 async fn ping_task(cmd_tx: UnboundedSender<Command>) -> Result<()> {
     const PING_TIMEOUT: Duration = Duration::from_secs(10);
     const HANG_TIMEOUT: Duration = Duration::from_secs(60 * 5);
@@ -259,12 +475,29 @@ async fn ping_task(cmd_tx: UnboundedSender<Command>) -> Result<()> {
     Ok(())
 }
 
+/// What to do when the server announces a workspace upgrade or maintenance
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum UpgradePolicy {
+    /// Keep sending, calls fail once the server goes away
+    #[default]
+    Fail,
+    /// Hold back new calls, reconnect once the server goes away, or after
+    /// [`WsBackendOpts::pause_timeout`], and send the held calls. Calls already sent are
+    /// repeated only if they are reads, others fail with [`Error::CallInterrupted`]
+    PauseAndReconnect,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct WsBackendOpts {
     pub binary: bool,
     pub compression: bool,
     /// How long to wait for the server's HELLO response before timing out
     pub hello_timeout: Duration,
+    pub upgrade_policy: UpgradePolicy,
+    /// How long calls are held back after an announced upgrade before reconnecting anyway
+    pub pause_timeout: Duration,
+    /// How long to keep trying to reconnect before failing the held calls
+    pub reconnect_timeout: Duration,
 }
 
 impl Default for WsBackendOpts {
@@ -273,6 +506,9 @@ impl Default for WsBackendOpts {
             binary: false,
             compression: false,
             hello_timeout: Duration::from_secs(10),
+            upgrade_policy: UpgradePolicy::Fail,
+            pause_timeout: Duration::from_secs(60),
+            reconnect_timeout: Duration::from_secs(60 * 5),
         }
     }
 }
@@ -301,26 +537,24 @@ impl WsBackend {
     ) -> Result<Self> {
         let token = token.into();
 
-        let url = base.join(token.expose_secret())?;
-        let resp = Client::default()
-            .get(url)
-            .bearer_auth(token.expose_secret())
-            .upgrade()
-            .send()
-            .await?;
-        let ws = resp.into_websocket().await?;
+        let ws = open_socket(&base, &token).await?;
 
-        let (write, read) = ws.split();
         let (hello_tx, hello_rx) = oneshot::channel();
 
         let (tx_broadcast, _) = broadcast::channel::<Value>(128);
 
-        let tx_broadcast_clone = tx_broadcast.clone();
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel::<Command>();
+        let socket_handle = connection_task(
+            ws,
+            base.clone(),
+            token.clone(),
+            cmd_rx,
+            SocketState::new(hello_tx),
+            opts,
+            tx_broadcast.clone(),
+        );
         let socket_handle = async move {
-            if let Err(e) =
-                socket_task(write, read, cmd_rx, opts, hello_tx, tx_broadcast_clone).await
-            {
+            if let Err(e) = socket_handle.await {
                 warn!(target:"ws", ?e, "socket task crashed");
             }
         };
@@ -357,14 +591,20 @@ impl WsBackend {
             }),
         })
     }
+}
 
-    pub(in crate::services::transactor) fn tx_stream(
-        &self,
-    ) -> tokio_stream::wrappers::BroadcastStream<Value> {
+impl SubscriptionBackend for WsBackend {
+    fn tx_stream(&self) -> tokio_stream::wrappers::BroadcastStream<Value> {
         self.inner.tx_broadcast.subscribe().into()
     }
 }
 
+impl Drop for WsBackendInner {
+    fn drop(&mut self) {
+        let _ = self.cmd_tx.send(Command::Close);
+    }
+}
+
 fn encode_message<Q: Serialize>(value: &Q, binary_mode: bool) -> Result<Message> {
     if binary_mode {
         Ok(Message::Binary(serde_json::to_vec(value)?.into()))
@@ -452,19 +692,36 @@ async fn send_and_wait<T: DeserializeOwned + Send, U: Serialize + Debug>(
     cmd_tx: &UnboundedSender<Command>,
     payload: Request<U>,
 ) -> Result<T> {
-    let payload = serde_json::to_value(&payload)?;
+    #[allow(unused_mut)]
+    let mut payload = serde_json::to_value(&payload)?;
+
+    // The platform's RPC request carries optional `meta`, trace context travels there
+    #[cfg(feature = "otel")]
+    {
+        let trace_context = crate::services::otel::trace_context();
+        if !trace_context.is_empty() {
+            payload["meta"] = serde_json::json!(trace_context);
+        }
+    }
 
-    let (reply_tx, reply_rx) = oneshot::channel();
-    cmd_tx.send(Command::Call { payload, reply_tx }).ok();
+    let method = payload["method"].as_str().unwrap_or_default().to_owned();
 
-    let Ok(reply) = reply_rx.await else {
-        return Err(Error::Other("connection closed before reply"));
-    };
+    metrics::measure(TRANSACTOR_WS, &method, async {
+        let _in_flight = metrics::InFlight::start();
 
-    let reply = reply?;
-    let Some(result) = reply.result else {
-        return Err(Error::Other("server didn't return a result"));
-    };
+        let (reply_tx, reply_rx) = oneshot::channel();
+        cmd_tx.send(Command::Call { payload, reply_tx }).ok();
+
+        let Ok(reply) = reply_rx.await else {
+            return Err(Error::Other("connection closed before reply"));
+        };
+
+        let reply = reply?;
+        let Some(result) = reply.result else {
+            return Err(Error::Other("server didn't return a result"));
+        };
 
-    serde_json::from_value(result).map_err(|e| e.into())
+        serde_json::from_value(result).map_err(|e| e.into())
+    })
+    .await
 }
@@ -0,0 +1,487 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use serde::Serialize;
+use serde_json::{self as json, Value};
+
+use crate::services::card::{self, Card, MasterTag, ParentInfo};
+use crate::services::core::classes::{Blobs, MarkupBlobRef, Rank, Ref};
+use crate::services::event::{Class, HasId};
+use crate::services::rank::make_rank;
+use crate::services::transactor::TransactorClient;
+use crate::services::transactor::backend::Backend;
+use crate::services::transactor::document::{
+    CreateDocument, DocumentClient, FindOptions, UpdateDocument, UpdateDocumentBuilder,
+    generate_object_id,
+};
+use crate::{Error, Result};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CardData {
+    title: String,
+    content: MarkupBlobRef,
+    blobs: Blobs,
+    parent_info: Vec<ParentInfo>,
+    parent: Option<Ref>,
+    rank: Rank,
+}
+
+#[derive(Debug, Clone, derive_builder::Builder)]
+pub struct CreateCardParams {
+    #[builder(setter(into))]
+    pub title: String,
+
+    #[builder(setter(into), default)]
+    pub content: MarkupBlobRef,
+
+    #[builder(setter(strip_option), default)]
+    pub parent: Option<Card>,
+
+    #[builder(setter(into), default = "card::space::Default.to_owned()")]
+    pub space: Ref,
+
+    /// Explicit rank, by default the card is placed after its last sibling
+    #[builder(setter(into, strip_option), default)]
+    pub rank: Option<Rank>,
+}
+
+impl CreateCardParams {
+    pub fn builder() -> CreateCardParamsBuilder {
+        CreateCardParamsBuilder::default()
+    }
+}
+
+pub trait CardClient {
+    /// Creates a card of the given [`MasterTag`], returns its id
+    fn create_card(
+        &self,
+        tag: &MasterTag,
+        params: &CreateCardParams,
+    ) -> impl Future<Output = Result<Ref>>;
+
+    /// Moves `card` under `parent` (or to the top level), updating `parentInfo` of the whole subtree
+    fn move_card(&self, card: &Card, parent: Option<&Card>) -> impl Future<Output = Result<()>>;
+
+    /// Places `card` between `prev` and `next` siblings, returns the new rank
+    fn reorder_card(
+        &self,
+        card: &Card,
+        prev: Option<&Card>,
+        next: Option<&Card>,
+    ) -> impl Future<Output = Result<Rank>>;
+
+    fn archive_card(&self, card: &Card) -> impl Future<Output = Result<()>>;
+
+    /// Removes `card` together with all its descendants
+    fn remove_card(&self, card: &Card) -> impl Future<Output = Result<()>>;
+
+    /// Direct children of `parent` (or top level cards), ordered by rank
+    fn card_children(&self, parent: Option<&Card>) -> impl Future<Output = Result<Vec<Card>>>;
+}
+
+fn update_card(card: &Card) -> UpdateDocumentBuilder {
+    let mut builder = UpdateDocument::builder();
+    builder
+        .object_id(card.id())
+        .object_class(&card.doc.obj.class)
+        .object_space(&card.doc.space);
+    builder
+}
+
+async fn children<B: Backend>(
+    client: &TransactorClient<B>,
+    parent: Option<&str>,
+) -> Result<Vec<Card>> {
+    let mut children = client
+        .find_all::<_, Card>(
+            Card::CLASS,
+            json::json!({ "parent": parent }),
+            &FindOptions::default(),
+        )
+        .await?
+        .value;
+
+    children.sort_by(|a, b| a.rank.cmp(&b.rank));
+
+    Ok(children)
+}
+
+async fn descendants<B: Backend>(client: &TransactorClient<B>, card: &Card) -> Result<Vec<Card>> {
+    Ok(client
+        .find_all::<_, Card>(
+            Card::CLASS,
+            json::json!({ "parentInfo._id": card.id() }),
+            &FindOptions::default(),
+        )
+        .await?
+        .value)
+}
+
+async fn next_child_rank<B: Backend>(
+    client: &TransactorClient<B>,
+    parent: Option<&str>,
+    exclude: Option<&str>,
+) -> Result<Rank> {
+    let last = children(client, parent)
+        .await?
+        .into_iter()
+        .filter(|c| Some(c.id()) != exclude)
+        .map(|c| c.rank)
+        .next_back();
+
+    make_rank(last.as_deref(), None)
+}
+
+impl<B: Backend> CardClient for TransactorClient<B> {
+    async fn create_card(&self, tag: &MasterTag, params: &CreateCardParams) -> Result<Ref> {
+        let parent = params.parent.as_ref();
+
+        let rank = match &params.rank {
+            Some(rank) => rank.clone(),
+            None => next_child_rank(self, parent.map(Card::id), None).await?,
+        };
+
+        let data = CardData {
+            title: params.title.clone(),
+            content: params.content.clone(),
+            blobs: Blobs::new(),
+            parent_info: parent.map(Card::child_parent_info).unwrap_or_default(),
+            parent: parent.map(|p| p.id().to_owned()),
+            rank,
+        };
+
+        let id = generate_object_id();
+
+        let tx = CreateDocument::builder()
+            .object_id(&id)
+            .object_class(tag.id())
+            .object_space(&params.space)
+            .attributes(data)
+            .build()
+            .expect("fields filled");
+
+        self.tx::<_, Value>(tx).await?;
+
+        Ok(id)
+    }
+
+    async fn move_card(&self, card: &Card, parent: Option<&Card>) -> Result<()> {
+        if let Some(parent) = parent
+            && (parent.id() == card.id() || parent.parent_info.iter().any(|p| p._id == card.id()))
+        {
+            return Err(Error::Other("CannotMoveCardIntoItself"));
+        }
+
+        let parent_info = parent.map(Card::child_parent_info).unwrap_or_default();
+        let rank = next_child_rank(self, parent.map(Card::id), Some(card.id())).await?;
+
+        let tx = update_card(card)
+            .set("parent", parent.map(|p| p.id().to_owned()))
+            .set("parentInfo", json::to_value(&parent_info)?)
+            .set("rank", rank)
+            .build()
+            .expect("fields filled");
+
+        self.tx::<_, Value>(tx).await?;
+
+        for descendant in descendants(self, card).await? {
+            let Some(position) = descendant
+                .parent_info
+                .iter()
+                .position(|p| p._id == card.id())
+            else {
+                continue;
+            };
+
+            let mut new_parent_info = parent_info.clone();
+            new_parent_info.extend_from_slice(&descendant.parent_info[position..]);
+
+            let tx = update_card(&descendant)
+                .set("parentInfo", json::to_value(&new_parent_info)?)
+                .build()
+                .expect("fields filled");
+
+            self.tx::<_, Value>(tx).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn reorder_card(
+        &self,
+        card: &Card,
+        prev: Option<&Card>,
+        next: Option<&Card>,
+    ) -> Result<Rank> {
+        let rank = make_rank(prev.map(|c| c.rank.as_str()), next.map(|c| c.rank.as_str()))?;
+
+        let tx = update_card(card)
+            .set("rank", rank.clone())
+            .build()
+            .expect("fields filled");
+
+        self.tx::<_, Value>(tx).await?;
+
+        Ok(rank)
+    }
+
+    async fn archive_card(&self, card: &Card) -> Result<()> {
+        let tx = update_card(card)
+            .set("archived", true)
+            .build()
+            .expect("fields filled");
+
+        self.tx::<_, Value>(tx).await?;
+
+        Ok(())
+    }
+
+    async fn remove_card(&self, card: &Card) -> Result<()> {
+        let mut descendants = descendants(self, card).await?;
+
+        // deepest first, so that no card is left without its parent
+        descendants.sort_by_key(|c| std::cmp::Reverse(c.parent_info.len()));
+
+        for descendant in descendants.iter().chain(std::iter::once(card)) {
+            self.remove(descendant).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn card_children(&self, parent: Option<&Card>) -> Result<Vec<Card>> {
+        children(self, parent.map(Card::id)).await
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::services::core::WorkspaceUuid;
+    use crate::services::transactor::backend::SubscriptionBackend;
+    use crate::testing::MemoryBackend;
+
+    async fn card(client: &TransactorClient<MemoryBackend>, id: &str) -> Card {
+        client
+            .find_one::<_, Card>(
+                Card::CLASS,
+                json::json!({ "_id": id }),
+                &FindOptions::default(),
+            )
+            .await
+            .unwrap()
+            .expect("card exists")
+    }
+
+    async fn create(
+        client: &TransactorClient<MemoryBackend>,
+        tag: &MasterTag,
+        title: &str,
+        parent: Option<&str>,
+    ) -> Card {
+        let mut params = CreateCardParams::builder();
+        params.title(title);
+        if let Some(parent) = parent {
+            params.parent(card(client, parent).await);
+        }
+
+        let id = client
+            .create_card(tag, &params.build().unwrap())
+            .await
+            .unwrap();
+
+        card(client, &id).await
+    }
+
+    fn parent_ids(card: &Card) -> Vec<&str> {
+        card.parent_info.iter().map(|p| p._id.as_str()).collect()
+    }
+
+    fn titles(cards: &[Card]) -> Vec<&str> {
+        cards.iter().map(|c| c.title.as_str()).collect()
+    }
+
+    fn memory_client() -> (MemoryBackend, TransactorClient<MemoryBackend>, MasterTag) {
+        let backend = MemoryBackend::new(WorkspaceUuid::nil());
+        let client = TransactorClient::new_memory(backend.clone());
+
+        backend
+            .insert(&json::json!({ "_id": "tag", "_class": card::class::MasterTag, "extends": Card::CLASS }))
+            .unwrap();
+        let mut tag = MasterTag::default();
+        tag.doc.id = "tag".into();
+
+        (backend, client, tag)
+    }
+
+    #[tokio::test]
+    async fn test_create_card() {
+        let (backend, client, tag) = memory_client();
+
+        let a = create(&client, &tag, "a", None).await;
+        let b = create(&client, &tag, "b", None).await;
+        assert!(a.rank < b.rank);
+        assert_eq!(a.parent, None);
+        assert!(a.parent_info.is_empty());
+        assert_eq!(a.doc.obj.class, "tag");
+
+        let child = create(&client, &tag, "child", Some(a.id())).await;
+        assert_eq!(child.parent.as_deref(), Some(a.id()));
+        assert_eq!(
+            child.parent_info,
+            [ParentInfo {
+                _id: a.id().to_owned(),
+                _class: "tag".into(),
+                title: "a".into(),
+            }]
+        );
+
+        let stored = backend
+            .snapshot()
+            .into_iter()
+            .find(|doc| doc["_id"] == child.id())
+            .unwrap();
+        assert_eq!(
+            stored["parentInfo"],
+            json::json!([{ "_id": a.id(), "_class": "tag", "title": "a" }])
+        );
+
+        // Ranks are per parent, the first child gets the same rank as the first top level card
+        assert_eq!(child.rank, a.rank);
+        let second = create(&client, &tag, "second", Some(a.id())).await;
+        assert!(child.rank < second.rank);
+
+        let rank = make_rank(None, Some(&a.rank)).unwrap();
+        let params = CreateCardParams::builder()
+            .title("first")
+            .rank(rank.clone())
+            .build()
+            .unwrap();
+        let id = client.create_card(&tag, &params).await.unwrap();
+        assert_eq!(card(&client, &id).await.rank, rank);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_card() {
+        let (_, client, tag) = memory_client();
+
+        let a = create(&client, &tag, "a", None).await;
+        let b = create(&client, &tag, "b", None).await;
+        let c = create(&client, &tag, "c", None).await;
+
+        let rank = client.reorder_card(&c, Some(&a), Some(&b)).await.unwrap();
+        assert!(a.rank < rank && rank < b.rank);
+        assert_eq!(card(&client, c.id()).await.rank, rank);
+
+        let children = client.card_children(None).await.unwrap();
+        assert_eq!(titles(&children), ["a", "c", "b"]);
+
+        let rank = client.reorder_card(&b, None, Some(&a)).await.unwrap();
+        assert!(rank < a.rank);
+
+        let children = client.card_children(None).await.unwrap();
+        assert_eq!(titles(&children), ["b", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_archive_card() {
+        let (_, client, tag) = memory_client();
+
+        let a = create(&client, &tag, "a", None).await;
+        let b = create(&client, &tag, "b", None).await;
+        assert_eq!(a.archived, None);
+
+        client.archive_card(&a).await.unwrap();
+
+        assert_eq!(card(&client, a.id()).await.archived, Some(true));
+        assert_eq!(card(&client, b.id()).await.archived, None);
+    }
+
+    #[tokio::test]
+    async fn test_card_children() {
+        let (_, client, tag) = memory_client();
+
+        // Inserted out of rank order
+        let middle = make_rank(None, None).unwrap();
+        let last = make_rank(Some(&middle), None).unwrap();
+        let first = make_rank(None, Some(&middle)).unwrap();
+
+        let mut ids = Vec::new();
+        for (title, rank) in [("last", last), ("first", first), ("middle", middle)] {
+            let params = CreateCardParams::builder()
+                .title(title)
+                .rank(rank)
+                .build()
+                .unwrap();
+            ids.push(client.create_card(&tag, &params).await.unwrap());
+        }
+
+        let middle = card(&client, &ids[2]).await;
+        let child = create(&client, &tag, "child", Some(middle.id())).await;
+        create(&client, &tag, "grandchild", Some(child.id())).await;
+
+        let top = client.card_children(None).await.unwrap();
+        assert_eq!(titles(&top), ["first", "middle", "last"]);
+
+        // Only direct children
+        let children = client.card_children(Some(&middle)).await.unwrap();
+        assert_eq!(titles(&children), ["child"]);
+    }
+
+    #[tokio::test]
+    async fn test_move_and_remove_card() {
+        let (backend, client, tag) = memory_client();
+
+        let a = create(&client, &tag, "a", None).await;
+        let b = create(&client, &tag, "b", None).await;
+        let child = create(&client, &tag, "child", Some(a.id())).await;
+        let grandchild = create(&client, &tag, "grandchild", Some(child.id())).await;
+        assert_eq!(parent_ids(&grandchild), [a.id(), child.id()]);
+
+        client.move_card(&child, Some(&b)).await.unwrap();
+
+        let child = card(&client, child.id()).await;
+        let grandchild = card(&client, grandchild.id()).await;
+        assert_eq!(child.parent.as_deref(), Some(b.id()));
+        assert_eq!(parent_ids(&child), [b.id()]);
+        assert_eq!(grandchild.parent.as_deref(), Some(child.id()));
+        assert_eq!(parent_ids(&grandchild), [b.id(), child.id()]);
+        assert_eq!(grandchild.parent_info[1].title, "child");
+
+        // Neither into itself nor under its own descendant
+        let b = card(&client, b.id()).await;
+        assert!(client.move_card(&b, Some(&b)).await.is_err());
+        assert!(client.move_card(&b, Some(&grandchild)).await.is_err());
+        assert_eq!(card(&client, b.id()).await.parent, None);
+
+        let mut txes = backend.tx_stream();
+        client.remove_card(&b).await.unwrap();
+
+        let mut removed = Vec::new();
+        for _ in 0..3 {
+            let tx = txes.next().await.unwrap().unwrap();
+            removed.push(tx["objectId"].as_str().unwrap().to_owned());
+        }
+        assert_eq!(removed, [grandchild.id(), child.id(), b.id()]);
+
+        let left = client.card_children(None).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].id(), a.id());
+        assert_eq!(backend.snapshot().len(), 2);
+    }
+}
@@ -0,0 +1,120 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use super::{CardId, CardType};
+use crate::services::core::classes::Timestamp;
+use crate::services::core::{AccountUuid, PersonId};
+
+pub type LabelId = String;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLabelEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub card_type: CardType,
+
+    #[builder(setter(into))]
+    pub label_id: LabelId,
+
+    pub account: AccountUuid,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub social_id: Option<PersonId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(CreateLabelEvent, card_id, CreateLabel);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveLabelEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub label_id: LabelId,
+
+    pub account: AccountUuid,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub social_id: Option<PersonId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(RemoveLabelEvent, card_id, RemoveLabel);
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::transactor::comm::Envelope;
+
+    #[test]
+    fn test_label_events() {
+        let create = CreateLabelEventBuilder::default()
+            .card_id("card")
+            .card_type("card:class:Card")
+            .label_id("label")
+            .account(AccountUuid::nil())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(create)).unwrap(),
+            json!({
+                "type": "createLabel",
+                "cardId": "card",
+                "cardType": "card:class:Card",
+                "labelId": "label",
+                "account": "00000000-0000-0000-0000-000000000000",
+            })
+        );
+
+        let remove = RemoveLabelEventBuilder::default()
+            .card_id("card")
+            .label_id("label")
+            .account(AccountUuid::nil())
+            .social_id("social")
+            .date(chrono::Utc.timestamp_opt(0, 0).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(remove)).unwrap(),
+            json!({
+                "type": "removeLabel",
+                "cardId": "card",
+                "labelId": "label",
+                "account": "00000000-0000-0000-0000-000000000000",
+                "socialId": "social",
+                "date": "1970-01-01T00:00:00Z",
+            })
+        );
+    }
+}
@@ -18,29 +18,26 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use serde_json as json;
 
+use super::MessageRequestType;
 use crate::services::core::PersonId;
 use crate::services::core::classes::{BlobMetadata, Timestamp};
 
-type Date = chrono::DateTime<chrono::Utc>;
+pub type Date = chrono::DateTime<chrono::Utc>;
 
-type MessageId = String;
-type CardId = String;
-type CardType = String;
-type Markdown = String;
-type BlobId = String;
+pub type MessageId = String;
+pub type CardId = String;
+pub type CardType = String;
+pub type Markdown = String;
+pub type BlobId = String;
+pub type LinkPreviewId = String;
 
 pub trait PartitionKeyProvider {
     fn partition_key(&self) -> &str;
 }
 
-macro_rules! message_event {
-    ($name:ident, $field:ident) => {
-        impl PartitionKeyProvider for $name {
-            fn partition_key(&self) -> &str {
-                &self.$field
-            }
-        }
-    };
+/// Event that can be sent to the communication domain
+pub trait CommunicationEvent: Serialize {
+    const TYPE: MessageRequestType;
 }
 
 pub type MessageExtra = HashMap<String, json::Value>;
@@ -101,7 +98,7 @@ pub struct CreateMessageEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<CreateMessageOptions>,
 }
-message_event!(CreateMessageEvent, card_id);
+message_event!(CreateMessageEvent, card_id, CreateMessage);
 
 #[derive(Serialize, Deserialize, Debug, Clone, Builder)]
 #[serde(rename_all = "camelCase")]
@@ -138,9 +135,9 @@ pub struct UpdatePatchEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<UpdatePatchOptions>,
 }
-message_event!(UpdatePatchEvent, card_id);
+message_event!(UpdatePatchEvent, card_id, UpdatePatch);
 
-#[derive(Serialize, Deserialize, Debug, Builder)]
+#[derive(Serialize, Deserialize, Debug, Clone, Builder)]
 #[serde(rename_all = "camelCase")]
 pub struct RemovePatchEvent {
     #[builder(setter(into))]
@@ -156,7 +153,7 @@ pub struct RemovePatchEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<Timestamp>,
 }
-message_event!(RemovePatchEvent, card_id);
+message_event!(RemovePatchEvent, card_id, RemovePatch);
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "opcode", rename_all = "lowercase")]
@@ -183,7 +180,7 @@ pub struct ReactionPatchEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<Timestamp>,
 }
-message_event!(ReactionPatchEvent, card_id);
+message_event!(ReactionPatchEvent, card_id, ReactionPatch);
 
 #[derive(Serialize, Deserialize, Debug, Builder, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -239,10 +236,352 @@ pub struct BlobPatchEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<Timestamp>,
 }
-message_event!(BlobPatchEvent, card_id);
+message_event!(BlobPatchEvent, card_id, BlobPatch);
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CreateMessageResult {
     pub message_id: MessageId,
     pub created: Date,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreviewImage {
+    #[builder(setter(into))]
+    pub url: String,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreviewData {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_id: Option<LinkPreviewId>,
+
+    #[builder(setter(into))]
+    pub url: String,
+
+    #[builder(setter(into))]
+    pub host: String,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_image: Option<LinkPreviewImage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "opcode", rename_all = "lowercase")]
+pub enum LinkPreviewPatchOperation {
+    Attach {
+        previews: Vec<LinkPreviewData>,
+    },
+    Detach {
+        #[serde(rename = "previewIds")]
+        preview_ids: Vec<LinkPreviewId>,
+    },
+    Set {
+        previews: Vec<LinkPreviewData>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreviewPatchEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub message_id: MessageId,
+
+    pub operations: Vec<LinkPreviewPatchOperation>,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(LinkPreviewPatchEvent, card_id, LinkPreviewPatch);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RepliesCountOperation {
+    Increment,
+    Decrement,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadUpdates {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_type: Option<CardType>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replies_count_op: Option<RepliesCountOperation>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_reply: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "opcode", rename_all = "lowercase")]
+pub enum ThreadPatchOperation {
+    Attach {
+        #[serde(rename = "threadId")]
+        thread_id: CardId,
+        #[serde(rename = "threadType")]
+        thread_type: CardType,
+    },
+    Update {
+        #[serde(rename = "threadId")]
+        thread_id: CardId,
+        updates: ThreadUpdates,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadPatchEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub message_id: MessageId,
+
+    pub operation: ThreadPatchOperation,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(ThreadPatchEvent, card_id, ThreadPatch);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagesGroup {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub blob_id: BlobId,
+
+    #[builder(setter(into))]
+    pub from_date: Date,
+
+    #[builder(setter(into))]
+    pub to_date: Date,
+
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessagesGroupEvent {
+    pub group: MessagesGroup,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(CreateMessagesGroupEvent, group.card_id, CreateMessagesGroup);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveMessagesGroupEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub blob_id: BlobId,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(RemoveMessagesGroupEvent, card_id, RemoveMessagesGroup);
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::transactor::comm::Envelope;
+
+    #[test]
+    fn test_create_message_event() {
+        let event = CreateMessageEventBuilder::default()
+            .card_id("card")
+            .card_type("card:class:Card")
+            .content("hello")
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        let value = serde_json::to_value(Envelope::from_event(event)).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "type": "createMessage",
+                "cardId": "card",
+                "cardType": "card:class:Card",
+                "messageType": "text",
+                "content": "hello",
+                "socialId": "social",
+            })
+        );
+
+        let envelope: Envelope<CreateMessageEvent> = serde_json::from_value(value).unwrap();
+        assert_eq!(envelope.r#type, MessageRequestType::CreateMessage);
+        assert_eq!(envelope.request.content, "hello");
+    }
+
+    #[test]
+    fn test_patch_events() {
+        let blobs = BlobPatchEventBuilder::default()
+            .card_id("card")
+            .message_id("message")
+            .operations(vec![
+                BlobPatchOperation::Attach {
+                    blobs: vec![
+                        BlobDataBuilder::default()
+                            .blob_id("blob")
+                            .mime_type("text/plain")
+                            .file_name("a.txt")
+                            .size(1u32)
+                            .build()
+                            .unwrap(),
+                    ],
+                },
+                BlobPatchOperation::Detach {
+                    blob_ids: vec!["old".into()],
+                },
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(blobs)).unwrap(),
+            json!({
+                "type": "blobPatch",
+                "cardId": "card",
+                "messageId": "message",
+                "operations": [
+                    {
+                        "opcode": "attach",
+                        "blobs": [{
+                            "blobId": "blob",
+                            "mimeType": "text/plain",
+                            "fileName": "a.txt",
+                            "size": 1,
+                        }],
+                    },
+                    { "opcode": "detach", "blobIds": ["old"] },
+                ],
+            })
+        );
+
+        let thread = ThreadPatchEventBuilder::default()
+            .card_id("card")
+            .message_id("message")
+            .operation(ThreadPatchOperation::Update {
+                thread_id: "thread".into(),
+                updates: ThreadUpdatesBuilder::default()
+                    .replies_count_op(RepliesCountOperation::Increment)
+                    .build()
+                    .unwrap(),
+            })
+            .social_id("social")
+            .date(chrono::Utc.timestamp_opt(0, 0).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(thread)).unwrap(),
+            json!({
+                "type": "threadPatch",
+                "cardId": "card",
+                "messageId": "message",
+                "operation": {
+                    "opcode": "update",
+                    "threadId": "thread",
+                    "updates": { "repliesCountOp": "increment" },
+                },
+                "socialId": "social",
+                "date": "1970-01-01T00:00:00Z",
+            })
+        );
+    }
+
+    #[test]
+    fn test_messages_group_event() {
+        let event = CreateMessagesGroupEventBuilder::default()
+            .group(
+                MessagesGroupBuilder::default()
+                    .card_id("card")
+                    .blob_id("blob")
+                    .from_date(chrono::Utc.timestamp_opt(0, 0).unwrap())
+                    .to_date(chrono::Utc.timestamp_opt(60, 0).unwrap())
+                    .count(2)
+                    .build()
+                    .unwrap(),
+            )
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        assert_eq!(event.partition_key(), "card");
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(event)).unwrap(),
+            json!({
+                "type": "createMessagesGroup",
+                "group": {
+                    "cardId": "card",
+                    "blobId": "blob",
+                    "fromDate": "1970-01-01T00:00:00Z",
+                    "toDate": "1970-01-01T00:01:00Z",
+                    "count": 2,
+                },
+                "socialId": "social",
+            })
+        );
+    }
+}
@@ -21,13 +21,49 @@ use crate::Result;
 use crate::services::JsonClient;
 use crate::services::core::classes::Ref;
 use crate::services::core::tx::{Tx, TxDomainEvent};
+use crate::services::event::DomainEvent;
+use crate::services::transactor::backend::Backend;
 use crate::services::transactor::backend::http::HttpBackend;
 use crate::services::transactor::document::generate_object_id;
 
+macro_rules! message_event {
+    ($name:ident, $($field:ident).+, $type:ident) => {
+        impl $crate::services::transactor::comm::PartitionKeyProvider for $name {
+            fn partition_key(&self) -> &str {
+                &self.$($field).+
+            }
+        }
+
+        impl $crate::services::transactor::comm::CommunicationEvent for $name {
+            const TYPE: $crate::services::transactor::comm::MessageRequestType =
+                $crate::services::transactor::comm::MessageRequestType::$type;
+        }
+
+        impl $crate::services::event::DomainEvent for $name {
+            const DOMAIN: &'static str = $crate::services::transactor::comm::DOMAIN;
+
+            fn matches(event: &serde_json::Value) -> bool {
+                serde_json::to_value(
+                    $crate::services::transactor::comm::MessageRequestType::$type,
+                )
+                .is_ok_and(|r#type| event.get("type") == Some(&r#type))
+            }
+        }
+    };
+}
+
+pub const DOMAIN: &str = "communication";
+
+mod label;
 mod message;
+mod notification;
+mod query;
+pub use label::*;
 pub use message::*;
+pub use notification::*;
+pub use query::*;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum MessageRequestType {
     // Message
@@ -75,6 +111,15 @@ impl<T: Serialize + DeserializeOwned> Envelope<T> {
     }
 }
 
+impl<T: CommunicationEvent> Envelope<T> {
+    pub fn from_event(event: T) -> Self {
+        Self {
+            r#type: T::TYPE,
+            request: event,
+        }
+    }
+}
+
 impl<T: Serialize> super::Transaction for Envelope<T> {
     fn to_value(self) -> Result<Value> {
         let event = TxDomainEvent {
@@ -95,21 +140,117 @@ impl<T: Serialize> super::Transaction for Envelope<T> {
                 object_space: "core:space:Domain".to_string(),
             },
 
-            domain: "communication".to_string(),
+            domain: DOMAIN.to_string(),
             event: self,
         };
         Ok(json::to_value(&event)?)
     }
 }
 
+/// Result of a communication domain transaction, which is either returned as is or wrapped into `result`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EventResult<R> {
+    Wrapped { result: R },
+    Plain(R),
+}
+
+impl<R> EventResult<R> {
+    fn into_inner(self) -> R {
+        match self {
+            EventResult::Wrapped { result } => result,
+            EventResult::Plain(result) => result,
+        }
+    }
+}
+
+pub trait CommunicationClient {
+    /// Sends any [`CommunicationEvent`] to the communication domain
+    fn send_event<E: CommunicationEvent, R: DeserializeOwned + Send>(
+        &self,
+        event: E,
+    ) -> impl Future<Output = Result<R>>;
+
+    fn create_message(
+        &self,
+        event: CreateMessageEvent,
+    ) -> impl Future<Output = Result<CreateMessageResult>> {
+        self.send_event(event)
+    }
+
+    fn update_message(&self, event: UpdatePatchEvent) -> impl Future<Output = Result<()>> {
+        async { self.send_event::<_, Value>(event).await.map(|_| ()) }
+    }
+
+    fn remove_message(&self, event: RemovePatchEvent) -> impl Future<Output = Result<()>> {
+        async { self.send_event::<_, Value>(event).await.map(|_| ()) }
+    }
+
+    fn react(&self, event: ReactionPatchEvent) -> impl Future<Output = Result<()>> {
+        async { self.send_event::<_, Value>(event).await.map(|_| ()) }
+    }
+
+    fn attach_blobs(&self, event: BlobPatchEvent) -> impl Future<Output = Result<()>> {
+        async { self.send_event::<_, Value>(event).await.map(|_| ()) }
+    }
+}
+
+impl<B: Backend> CommunicationClient for super::TransactorClient<B> {
+    async fn send_event<E: CommunicationEvent, R: DeserializeOwned + Send>(
+        &self,
+        event: E,
+    ) -> Result<R> {
+        let result: EventResult<R> = self.tx(Envelope::from_event(event)).await?;
+        Ok(result.into_inner())
+    }
+}
+
+/// Message related events broadcast by the communication domain
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MessageEvent {
+    #[serde(rename = "createMessage")]
+    Created(CreateMessageEvent),
+    #[serde(rename = "updatePatch")]
+    Updated(UpdatePatchEvent),
+    #[serde(rename = "removePatch")]
+    Removed(RemovePatchEvent),
+    #[serde(rename = "reactionPatch")]
+    Reaction(ReactionPatchEvent),
+    #[serde(rename = "blobPatch")]
+    Blob(BlobPatchEvent),
+    #[serde(rename = "linkPreviewPatch")]
+    LinkPreview(LinkPreviewPatchEvent),
+    #[serde(rename = "threadPatch")]
+    Thread(ThreadPatchEvent),
+}
+
+impl DomainEvent for MessageEvent {
+    const DOMAIN: &'static str = DOMAIN;
+
+    fn matches(event: &Value) -> bool {
+        [
+            CreateMessageEvent::matches,
+            UpdatePatchEvent::matches,
+            RemovePatchEvent::matches,
+            ReactionPatchEvent::matches,
+            BlobPatchEvent::matches,
+            LinkPreviewPatchEvent::matches,
+            ThreadPatchEvent::matches,
+        ]
+        .iter()
+        .any(|matches| matches(event))
+    }
+}
+
 pub trait EventClient {
-    #[deprecated = "use transactor directly"]
+    #[deprecated = "use CommunicationClient"]
     fn request_raw<T: Serialize + DeserializeOwned, R: DeserializeOwned>(
         &self,
         body: &T,
     ) -> impl Future<Output = Result<R>>;
 
-    #[deprecated = "use transactor directly"]
+    #[deprecated = "use CommunicationClient"]
     #[allow(deprecated)]
     fn request_for_result<T: Serialize + DeserializeOwned, R: DeserializeOwned>(
         &self,
@@ -119,7 +260,7 @@ pub trait EventClient {
         async { self.request_raw(&Envelope::new(r#type, request)).await }
     }
 
-    #[deprecated = "use transactor directly"]
+    #[deprecated = "use CommunicationClient"]
     #[allow(deprecated)]
     fn request<T: Serialize + DeserializeOwned>(
         &self,
@@ -142,3 +283,34 @@ impl EventClient for super::TransactorClient<HttpBackend> {
         <HttpBackend as JsonClient>::post(self.backend(), &self, url, envelope).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_message_event() {
+        let event = json!({
+            "type": "reactionPatch",
+            "cardId": "card",
+            "messageId": "message",
+            "operation": { "opcode": "add", "reaction": "👍" },
+            "socialId": "social",
+        });
+
+        assert!(MessageEvent::matches(&event));
+        assert!(ReactionPatchEvent::matches(&event));
+        assert!(!CreateMessageEvent::matches(&event));
+        assert!(matches!(
+            json::from_value::<MessageEvent>(event).unwrap(),
+            MessageEvent::Reaction(ReactionPatchEvent {
+                operation: ReactionPatchOperation::Add { .. },
+                ..
+            })
+        ));
+
+        assert!(!MessageEvent::matches(&json!({ "type": "createLabel" })));
+    }
+}
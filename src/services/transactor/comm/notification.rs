@@ -0,0 +1,380 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+
+use super::{CardId, CardType, Date, MessageId};
+use crate::services::core::classes::Timestamp;
+use crate::services::core::{AccountUuid, PersonId};
+
+pub type ContextId = String;
+pub type NotificationId = String;
+pub type NotificationContent = HashMap<String, json::Value>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationType {
+    #[default]
+    Message,
+    Reaction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct AddCollaboratorsEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub card_type: CardType,
+
+    pub collaborators: Vec<AccountUuid>,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(AddCollaboratorsEvent, card_id, AddCollaborators);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveCollaboratorsEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub card_type: CardType,
+
+    pub collaborators: Vec<AccountUuid>,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(RemoveCollaboratorsEvent, card_id, RemoveCollaborators);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNotificationEvent {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_id: Option<NotificationId>,
+
+    #[builder(default)]
+    pub notification_type: NotificationType,
+
+    #[builder(default)]
+    pub read: bool,
+
+    #[builder(default)]
+    pub content: NotificationContent,
+
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    #[builder(setter(into))]
+    pub context_id: ContextId,
+
+    #[builder(setter(into))]
+    pub message_id: MessageId,
+
+    #[builder(setter(into))]
+    pub message_created: Date,
+
+    pub account: AccountUuid,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(CreateNotificationEvent, card_id, CreateNotification);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationQuery {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<NotificationId>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<NotificationType>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until_date: Option<Date>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationUpdates {
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationEvent {
+    #[builder(setter(into))]
+    pub context_id: ContextId,
+
+    pub account: AccountUuid,
+
+    #[builder(default)]
+    pub query: UpdateNotificationQuery,
+
+    pub updates: NotificationUpdates,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(UpdateNotificationEvent, context_id, UpdateNotification);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveNotificationsEvent {
+    #[builder(setter(into))]
+    pub context_id: ContextId,
+
+    pub account: AccountUuid,
+
+    pub ids: Vec<NotificationId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub social_id: Option<PersonId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(RemoveNotificationsEvent, context_id, RemoveNotifications);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNotificationContextEvent {
+    #[builder(setter(into))]
+    pub card_id: CardId,
+
+    pub account: AccountUuid,
+
+    #[builder(setter(into))]
+    pub last_update: Date,
+
+    #[builder(setter(into))]
+    pub last_view: Date,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_notify: Option<Date>,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(
+    CreateNotificationContextEvent,
+    card_id,
+    CreateNotificationContext
+);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveNotificationContextEvent {
+    #[builder(setter(into))]
+    pub context_id: ContextId,
+
+    pub account: AccountUuid,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(
+    RemoveNotificationContextEvent,
+    context_id,
+    RemoveNotificationContext
+);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationContextUpdates {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_view: Option<Date>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<Date>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_notify: Option<Date>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationContextEvent {
+    #[builder(setter(into))]
+    pub context_id: ContextId,
+
+    pub account: AccountUuid,
+
+    pub updates: NotificationContextUpdates,
+
+    #[builder(setter(into))]
+    pub social_id: PersonId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Timestamp>,
+}
+message_event!(
+    UpdateNotificationContextEvent,
+    context_id,
+    UpdateNotificationContext
+);
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::transactor::comm::Envelope;
+
+    #[test]
+    fn test_notification_events() {
+        let create = CreateNotificationEventBuilder::default()
+            .card_id("card")
+            .context_id("context")
+            .message_id("message")
+            .message_created(chrono::Utc.timestamp_opt(0, 0).unwrap())
+            .account(AccountUuid::nil())
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(create)).unwrap(),
+            json!({
+                "type": "createNotification",
+                "notificationType": "message",
+                "read": false,
+                "content": {},
+                "cardId": "card",
+                "contextId": "context",
+                "messageId": "message",
+                "messageCreated": "1970-01-01T00:00:00Z",
+                "account": "00000000-0000-0000-0000-000000000000",
+                "socialId": "social",
+            })
+        );
+
+        let update = UpdateNotificationEventBuilder::default()
+            .context_id("context")
+            .account(AccountUuid::nil())
+            .query(
+                UpdateNotificationQueryBuilder::default()
+                    .r#type(NotificationType::Reaction)
+                    .build()
+                    .unwrap(),
+            )
+            .updates(NotificationUpdates { read: true })
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(update)).unwrap(),
+            json!({
+                "type": "updateNotification",
+                "contextId": "context",
+                "account": "00000000-0000-0000-0000-000000000000",
+                "query": { "type": "reaction" },
+                "updates": { "read": true },
+                "socialId": "social",
+            })
+        );
+    }
+
+    #[test]
+    fn test_notification_context_events() {
+        let update = UpdateNotificationContextEventBuilder::default()
+            .context_id("context")
+            .account(AccountUuid::nil())
+            .updates(
+                NotificationContextUpdatesBuilder::default()
+                    .last_view(chrono::Utc.timestamp_opt(0, 0).unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(update)).unwrap(),
+            json!({
+                "type": "updateNotificationContext",
+                "contextId": "context",
+                "account": "00000000-0000-0000-0000-000000000000",
+                "updates": { "lastView": "1970-01-01T00:00:00Z" },
+                "socialId": "social",
+            })
+        );
+
+        let collaborators = AddCollaboratorsEventBuilder::default()
+            .card_id("card")
+            .card_type("card:class:Card")
+            .collaborators(vec![AccountUuid::nil()])
+            .social_id("social")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(Envelope::from_event(collaborators)).unwrap(),
+            json!({
+                "type": "addCollaborators",
+                "cardId": "card",
+                "cardType": "card:class:Card",
+                "collaborators": ["00000000-0000-0000-0000-000000000000"],
+                "socialId": "social",
+            })
+        );
+    }
+}
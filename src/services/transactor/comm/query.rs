@@ -0,0 +1,679 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashSet;
+
+use derive_builder::Builder;
+use futures::{Stream, TryStreamExt};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    BlobId, CardId, CardType, ContextId, DOMAIN, Date, LinkPreviewId, LinkPreviewImage, Markdown,
+    MessageExtra, MessageId, MessageType, NotificationContent, NotificationId, NotificationType,
+};
+use crate::Result;
+use crate::services::core::classes::BlobMetadata;
+use crate::services::core::{AccountUuid, PersonId};
+use crate::services::transactor::TransactorClient;
+use crate::services::transactor::backend::Backend;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortingOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl Serialize for SortingOrder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            SortingOrder::Ascending => serializer.serialize_i8(1),
+            SortingOrder::Descending => serializer.serialize_i8(-1),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SortingOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match i8::deserialize(deserializer)? {
+            1 => Ok(SortingOrder::Ascending),
+            -1 => Ok(SortingOrder::Descending),
+            other => Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Signed(other as i64),
+                &"1 or -1",
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct DateFilter {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub less: Option<Date>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub less_or_equal: Option<Date>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub greater: Option<Date>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub greater_or_equal: Option<Date>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct FindMessagesParams {
+    #[builder(setter(into))]
+    pub card: CardId,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<MessageId>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateFilter>,
+
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub files: bool,
+
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reactions: bool,
+
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub replies: bool,
+
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub links: bool,
+
+    #[builder(default)]
+    pub order: SortingOrder,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl FindMessagesParams {
+    pub fn builder() -> FindMessagesParamsBuilder {
+        FindMessagesParamsBuilder::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct FindThreadParams {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<CardId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<MessageId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<CardId>,
+
+    #[builder(default)]
+    pub order: SortingOrder,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl FindThreadParams {
+    pub fn builder() -> FindThreadParamsBuilder {
+        FindThreadParamsBuilder::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct FindNotificationContextParams {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<ContextId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card: Option<CardId>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountUuid>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_notify: Option<DateFilter>,
+
+    #[builder(default)]
+    pub order: SortingOrder,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl FindNotificationContextParams {
+    pub fn builder() -> FindNotificationContextParamsBuilder {
+        FindNotificationContextParamsBuilder::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct FindNotificationsParams {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<ContextId>,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card: Option<CardId>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountUuid>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<NotificationType>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read: Option<bool>,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateFilter>,
+
+    #[builder(default)]
+    pub order: SortingOrder,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl FindNotificationsParams {
+    pub fn builder() -> FindNotificationsParamsBuilder {
+        FindNotificationsParamsBuilder::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Reaction {
+    pub reaction: String,
+    pub creator: PersonId,
+    pub created: Date,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedBlob {
+    pub blob_id: BlobId,
+    pub mime_type: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub size: u32,
+    pub metadata: Option<BlobMetadata>,
+    pub creator: PersonId,
+    pub created: Date,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreview {
+    pub preview_id: LinkPreviewId,
+    pub url: String,
+    pub host: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub icon_url: Option<String>,
+    pub preview_image: Option<LinkPreviewImage>,
+    pub creator: PersonId,
+    pub created: Date,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub card_id: CardId,
+    pub message_id: MessageId,
+    pub thread_id: CardId,
+    pub thread_type: CardType,
+    pub replies_count: u32,
+    pub last_reply: Option<Date>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub id: MessageId,
+    pub card_id: CardId,
+    #[serde(default)]
+    pub r#type: MessageType,
+    pub content: Markdown,
+    pub extra: Option<MessageExtra>,
+    pub creator: PersonId,
+    pub created: Date,
+    #[serde(default)]
+    pub removed: bool,
+    pub edited: Option<Date>,
+    pub thread: Option<Thread>,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+    #[serde(default)]
+    pub blobs: Vec<AttachedBlob>,
+    #[serde(default)]
+    pub link_previews: Vec<LinkPreview>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: NotificationId,
+    pub card_id: CardId,
+    pub context_id: ContextId,
+    pub account: AccountUuid,
+    #[serde(default)]
+    pub r#type: NotificationType,
+    pub read: bool,
+    pub message_id: Option<MessageId>,
+    pub message_created: Option<Date>,
+    pub creator: Option<PersonId>,
+    pub created: Date,
+    #[serde(default)]
+    pub content: NotificationContent,
+    pub message: Option<Message>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationContext {
+    pub id: ContextId,
+    pub card_id: CardId,
+    pub account: AccountUuid,
+    pub last_update: Date,
+    pub last_view: Date,
+    pub last_notify: Option<Date>,
+    pub notifications: Option<Vec<Notification>>,
+    pub total_notifications: Option<u32>,
+}
+
+pub trait CommunicationQueryClient {
+    fn find_messages(
+        &self,
+        params: &FindMessagesParams,
+    ) -> impl Future<Output = Result<Vec<Message>>>;
+
+    fn find_threads(&self, params: &FindThreadParams) -> impl Future<Output = Result<Vec<Thread>>>;
+
+    fn find_notification_contexts(
+        &self,
+        params: &FindNotificationContextParams,
+    ) -> impl Future<Output = Result<Vec<NotificationContext>>>;
+
+    fn find_notifications(
+        &self,
+        params: &FindNotificationsParams,
+    ) -> impl Future<Output = Result<Vec<Notification>>>;
+
+    /// Pages through messages of a card using `created` as the cursor.
+    ///
+    /// Each page holds up to `params.limit` messages (50 by default), the next page starts at
+    /// the `created` of the last message of the previous one in `params.order`, skipping the
+    /// messages already returned at that timestamp.
+    fn message_pages(&self, params: FindMessagesParams)
+    -> impl Stream<Item = Result<Vec<Message>>>;
+
+    /// Messages of a card one by one, see [`CommunicationQueryClient::message_pages`]
+    fn messages_stream(&self, params: FindMessagesParams) -> impl Stream<Item = Result<Message>> {
+        self.message_pages(params)
+            .map_ok(|page| futures::stream::iter(page.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Thread attached to `message`, with the messages posted into it
+    fn load_thread(
+        &self,
+        card: &str,
+        message: &str,
+    ) -> impl Future<Output = Result<Option<(Thread, Vec<Message>)>>>;
+}
+
+impl<B: Backend> CommunicationQueryClient for TransactorClient<B> {
+    async fn find_messages(&self, params: &FindMessagesParams) -> Result<Vec<Message>> {
+        Ok(self
+            .domain_request(DOMAIN.into(), "findMessages", params)
+            .await?
+            .value)
+    }
+
+    async fn find_threads(&self, params: &FindThreadParams) -> Result<Vec<Thread>> {
+        Ok(self
+            .domain_request(DOMAIN.into(), "findThreads", params)
+            .await?
+            .value)
+    }
+
+    async fn find_notification_contexts(
+        &self,
+        params: &FindNotificationContextParams,
+    ) -> Result<Vec<NotificationContext>> {
+        Ok(self
+            .domain_request(DOMAIN.into(), "findNotificationContexts", params)
+            .await?
+            .value)
+    }
+
+    async fn find_notifications(
+        &self,
+        params: &FindNotificationsParams,
+    ) -> Result<Vec<Notification>> {
+        Ok(self
+            .domain_request(DOMAIN.into(), "findNotifications", params)
+            .await?
+            .value)
+    }
+
+    fn message_pages(
+        &self,
+        mut params: FindMessagesParams,
+    ) -> impl Stream<Item = Result<Vec<Message>>> {
+        const DEFAULT_PAGE_SIZE: u32 = 50;
+
+        let limit = params.limit.take().unwrap_or(DEFAULT_PAGE_SIZE);
+        let client = self.clone();
+
+        // The cursor is inclusive, `seen` holds the ids already returned at the cursor timestamp
+        futures::stream::try_unfold(Some((params, HashSet::new())), move |state| {
+            let client = client.clone();
+
+            async move {
+                let Some((mut params, mut seen)) = state else {
+                    return Ok(None);
+                };
+
+                // Asks for the seen messages on top, so that a page always has `limit` new ones
+                let requested = limit + seen.len() as u32;
+                params.limit = Some(requested);
+
+                let page = client.find_messages(&params).await?;
+                let full = page.len() as u32 >= requested;
+
+                let page = page
+                    .into_iter()
+                    .filter(|message| !seen.contains(&message.id))
+                    .collect::<Vec<_>>();
+
+                let Some(last) = page.last() else {
+                    return Ok(None);
+                };
+
+                let next = if full {
+                    let mut created = params.created.take().unwrap_or_default();
+                    let cursor = match params.order {
+                        SortingOrder::Ascending => {
+                            created.greater = None;
+                            created.greater_or_equal.replace(last.created)
+                        }
+                        SortingOrder::Descending => {
+                            created.less = None;
+                            created.less_or_equal.replace(last.created)
+                        }
+                    };
+
+                    if cursor != Some(last.created) {
+                        seen.clear();
+                    }
+
+                    seen.extend(
+                        page.iter()
+                            .filter(|message| message.created == last.created)
+                            .map(|message| message.id.clone()),
+                    );
+
+                    params.created = Some(created);
+                    Some((params, seen))
+                } else {
+                    None
+                };
+
+                Ok(Some((page, next)))
+            }
+        })
+    }
+
+    async fn load_thread(
+        &self,
+        card: &str,
+        message: &str,
+    ) -> Result<Option<(Thread, Vec<Message>)>> {
+        let params = FindThreadParams {
+            card_id: Some(card.to_owned()),
+            message_id: Some(message.to_owned()),
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let Some(thread) = self.find_threads(&params).await?.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let messages = self
+            .messages_stream(FindMessagesParams {
+                card: thread.thread_id.clone(),
+                ..Default::default()
+            })
+            .try_collect()
+            .await?;
+
+        Ok(Some((thread, messages)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{TimeZone, Utc};
+    use futures::TryStreamExt;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use url::Url;
+
+    use super::*;
+    use crate::Error;
+    use crate::services::TokenProvider;
+    use crate::services::core::WorkspaceUuid;
+    use crate::services::core::classes::OperationDomain;
+    use crate::services::core::storage::DomainResult;
+    use crate::services::transactor::methods::Method;
+
+    /// Answers `findMessages` from a fixed list of messages, sorted by `created` only
+    #[derive(Clone)]
+    struct Messages {
+        messages: Arc<Vec<Message>>,
+        requests: Arc<Mutex<Vec<Value>>>,
+        base: Url,
+    }
+
+    impl Messages {
+        fn new(created: &[i64]) -> Self {
+            let messages = created
+                .iter()
+                .enumerate()
+                .map(|(index, created)| {
+                    serde_json::from_value(json!({
+                        "id": format!("m{index}"),
+                        "cardId": "card",
+                        "content": "",
+                        "creator": "creator",
+                        "created": Utc.timestamp_opt(*created, 0).unwrap(),
+                    }))
+                    .unwrap()
+                })
+                .collect();
+
+            Self {
+                messages: Arc::new(messages),
+                requests: Arc::default(),
+                base: Url::parse("memory://transactor/").unwrap(),
+            }
+        }
+
+        fn ids<'a>(messages: impl IntoIterator<Item = &'a Message>) -> Vec<&'a str> {
+            messages.into_iter().map(|m| m.id.as_str()).collect()
+        }
+    }
+
+    impl TokenProvider for Messages {
+        fn provide_token(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    impl Backend for Messages {
+        async fn get<T: DeserializeOwned + Send>(
+            &self,
+            _method: Method,
+            _params: impl IntoIterator<Item = (String, Value)>,
+        ) -> Result<T> {
+            Err(Error::Other("UnsupportedMethod"))
+        }
+
+        async fn post<T: DeserializeOwned + Send, Q: Serialize>(
+            &self,
+            _method: Method,
+            _body: &Q,
+        ) -> Result<T> {
+            Err(Error::Other("UnsupportedMethod"))
+        }
+
+        async fn domain_request<T: DeserializeOwned + Send, Q: Serialize>(
+            &self,
+            domain: OperationDomain,
+            _operation: &str,
+            params: &Q,
+        ) -> Result<DomainResult<T>> {
+            let params = serde_json::to_value(params)?;
+            self.requests.lock().unwrap().push(params.clone());
+
+            let created =
+                serde_json::from_value::<DateFilter>(params["created"].clone()).unwrap_or_default();
+            let order = serde_json::from_value::<SortingOrder>(params["order"].clone())?;
+            let mut page = self
+                .messages
+                .iter()
+                .filter(|m| created.greater.is_none_or(|date| m.created > date))
+                .filter(|m| {
+                    created
+                        .greater_or_equal
+                        .is_none_or(|date| m.created >= date)
+                })
+                .filter(|m| created.less.is_none_or(|date| m.created < date))
+                .filter(|m| created.less_or_equal.is_none_or(|date| m.created <= date))
+                .collect::<Vec<_>>();
+
+            match order {
+                SortingOrder::Ascending => page.sort_by_key(|m| m.created),
+                SortingOrder::Descending => page.sort_by_key(|m| std::cmp::Reverse(m.created)),
+            }
+            page.truncate(params["limit"].as_u64().unwrap_or(u64::MAX) as usize);
+
+            Ok(DomainResult {
+                domain,
+                value: serde_json::from_value(serde_json::to_value(page)?)?,
+            })
+        }
+
+        async fn tx_raw<T: Serialize, R: DeserializeOwned + Send>(&self, _tx: T) -> Result<R> {
+            Err(Error::Other("UnsupportedMethod"))
+        }
+
+        fn base(&self) -> &Url {
+            &self.base
+        }
+
+        fn workspace(&self) -> WorkspaceUuid {
+            WorkspaceUuid::nil()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_pages() {
+        // Four messages share a timestamp across the boundary of the first page
+        let backend = Messages::new(&[1, 2, 2, 2, 2, 3, 4]);
+        let client = TransactorClient {
+            backend: backend.clone(),
+        };
+
+        let params = FindMessagesParams::builder()
+            .card("card")
+            .limit(2)
+            .build()
+            .unwrap();
+        let pages = client
+            .message_pages(params.clone())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        let pages = pages.iter().map(Messages::ids).collect::<Vec<_>>();
+        assert_eq!(
+            pages,
+            [
+                vec!["m0", "m1"],
+                vec!["m2", "m3"],
+                vec!["m4", "m5"],
+                vec!["m6"]
+            ]
+        );
+
+        let requests = backend.requests.lock().unwrap().clone();
+        assert_eq!(requests[1]["limit"], 3);
+        assert_eq!(requests[2]["limit"], 5);
+        assert_eq!(requests[3]["limit"], 3);
+
+        let descending = FindMessagesParams {
+            order: SortingOrder::Descending,
+            ..params
+        };
+        let messages = client
+            .messages_stream(descending)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            Messages::ids(&messages),
+            ["m6", "m5", "m1", "m2", "m3", "m4", "m0"]
+        );
+    }
+}
@@ -27,7 +27,7 @@ use super::{
 
 use crate::services::core::classes::{Ref, Timestamp};
 use crate::services::core::ser::Data;
-use crate::services::core::tx::{Tx, TxCUD, TxCreateDoc, TxRemoveDoc};
+use crate::services::core::tx::{DocumentUpdate, Tx, TxCUD, TxCreateDoc, TxRemoveDoc, TxUpdateDoc};
 use crate::services::core::{Account, FindResult, PersonId};
 use crate::services::transactor::backend::Backend;
 use crate::services::transactor::methods::Method;
@@ -178,6 +178,81 @@ impl Transaction for RemoveDocument {
     }
 }
 
+#[derive(Default, Debug, derive_builder::Builder, Clone)]
+pub struct UpdateDocument {
+    #[builder(setter(into))]
+    object_id: Ref,
+
+    #[builder(setter(into))]
+    object_class: String,
+
+    #[builder(setter(into), default = Utc::now())]
+    modified_on: Timestamp,
+
+    #[builder(setter(into, strip_option), default)]
+    modified_by: Option<PersonId>,
+
+    #[builder(setter(into))]
+    object_space: String,
+
+    #[builder(setter(custom), default)]
+    operations: DocumentUpdate,
+
+    #[builder(setter(strip_option), default)]
+    retrieve: Option<bool>,
+}
+
+impl UpdateDocument {
+    pub fn builder() -> UpdateDocumentBuilder {
+        UpdateDocumentBuilder::default()
+    }
+}
+
+impl UpdateDocumentBuilder {
+    /// Sets `field` to `value`
+    pub fn set(&mut self, field: &str, value: impl Into<Value>) -> &mut Self {
+        self.operations
+            .get_or_insert_with(DocumentUpdate::default)
+            .set_operations
+            .insert(field.to_owned(), value.into());
+        self
+    }
+}
+
+impl Transaction for UpdateDocument {
+    fn to_value(self) -> Result<Value> {
+        let doc = TxUpdateDoc::<()> {
+            txcud: TxCUD {
+                tx: Tx {
+                    doc: Doc {
+                        obj: Obj {
+                            class: Ref::from(crate::services::core::class::TxUpdateDoc),
+                        },
+
+                        id: generate_object_id(),
+                        modified_on: Some(self.modified_on),
+                        modified_by: self.modified_by,
+                        created_on: None,
+                        created_by: None,
+                        space: Ref::from(crate::services::core::space::Tx),
+                    },
+                    object_space: self.object_space,
+                },
+                object_id: self.object_id,
+                object_class: self.object_class,
+                attached_to: None,
+                attached_to_class: None,
+                collection: None,
+            },
+            operations: self.operations,
+            retrieve: self.retrieve,
+            _phantom: Default::default(),
+        };
+
+        Ok(json::to_value(&doc)?)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum LookupValue {
@@ -0,0 +1,815 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::time::Duration;
+
+use rdkafka::{
+    ClientConfig, Message, Offset,
+    consumer::{CommitMode, Consumer, ConsumerContext, StreamConsumer},
+    message::BorrowedMessage,
+    message::{Header, Headers, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord, future_producer::Delivery},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{self as json, Value};
+use tracing::{Instrument, debug, warn};
+use uuid::Uuid;
+
+use super::Transaction;
+use crate::services::core::WorkspaceUuid;
+use crate::services::core::tx::TxDomainEvent;
+use crate::services::event::{Class, DomainEvent};
+use crate::services::metrics;
+use crate::services::transactor::comm::{Envelope, PartitionKeyProvider};
+use crate::services::transactor::subscription::{TxEvent, is_domain_event};
+use crate::{Config, Error, Result};
+
+/// Headers carrying the workspace of a transaction, in order of preference
+const WORKSPACE_HEADERS: [&str; 2] = ["WorkspaceUuid", "workspace"];
+
+#[derive(Debug, Clone, derive_builder::Builder)]
+pub struct KafkaProducerOpts {
+    /// How long to wait for more messages to batch them together
+    #[builder(default = "Duration::from_millis(5)")]
+    pub linger: Duration,
+
+    /// Avoid duplicates and reordering on retries, requires acknowledgement from all replicas
+    #[builder(default)]
+    pub idempotent: bool,
+
+    /// How long to try delivering a message before reporting a failure
+    #[builder(default = "Duration::from_secs(5)")]
+    pub message_timeout: Duration,
+}
+
+impl KafkaProducerOpts {
+    pub fn builder() -> KafkaProducerOptsBuilder {
+        KafkaProducerOptsBuilder::default()
+    }
+}
+
+impl Default for KafkaProducerOpts {
+    fn default() -> Self {
+        Self::builder().build().expect("all fields have defaults")
+    }
+}
+
+/// Where a message has been stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub partition: i32,
+    pub offset: i64,
+}
+
+impl From<Delivery> for DeliveryReport {
+    fn from(delivery: Delivery) -> Self {
+        Self {
+            partition: delivery.partition,
+            offset: delivery.offset,
+        }
+    }
+}
+
+pub struct KafkaProducer {
+    producer: FutureProducer,
+    topic: String,
+}
+
+fn tx_headers(workspace: WorkspaceUuid) -> OwnedHeaders {
+    let headers = OwnedHeaders::new()
+        .insert(Header {
+            key: "WorkspaceUuid",
+            value: Some(&workspace.to_string()),
+        })
+        .insert(Header {
+            key: "workspace",
+            value: Some(&workspace.to_string()),
+        })
+        .insert(Header {
+            key: "Mode",
+            value: Some("transaction"),
+        });
+
+    #[cfg(feature = "otel")]
+    let headers =
+        crate::services::otel::trace_context()
+            .iter()
+            .fold(headers, |headers, (key, value)| {
+                headers.insert(Header {
+                    key,
+                    value: Some(value),
+                })
+            });
+
+    headers
+}
+
+impl KafkaProducer {
+    pub fn new(config: &Config, topic: &str) -> Result<Self> {
+        Self::with_opts(config, topic, &KafkaProducerOpts::default())
+    }
+
+    pub fn with_opts(config: &Config, topic: &str, opts: &KafkaProducerOpts) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set(
+                "bootstrap.servers",
+                config.kafka_bootstrap_servers.join(","),
+            )
+            .set(
+                "message.timeout.ms",
+                opts.message_timeout.as_millis().to_string(),
+            )
+            .set("linger.ms", opts.linger.as_millis().to_string())
+            .set("enable.idempotence", opts.idempotent.to_string())
+            .create()?;
+
+        Ok(Self {
+            producer,
+            topic: topic.to_owned(),
+        })
+    }
+
+    pub async fn tx_raw<T: Serialize>(
+        &self,
+        workspace: WorkspaceUuid,
+        transaction: T,
+        partition_key: Option<&str>,
+    ) -> Result<()> {
+        let payload = json::to_vec(&transaction)?;
+
+        self.send(
+            &self.topic,
+            &payload,
+            partition_key.map(str::as_bytes),
+            tx_headers(workspace),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn tx<T: Transaction>(
+        &self,
+        workspace: WorkspaceUuid,
+        tx: T,
+        partition_key: Option<&str>,
+    ) -> Result<()> {
+        self.tx_raw(workspace, tx.to_value()?, partition_key).await
+    }
+
+    /// Sends a communication event, partitioned by its [`PartitionKeyProvider::partition_key`]
+    pub async fn send_event<T: Serialize + PartitionKeyProvider>(
+        &self,
+        workspace: WorkspaceUuid,
+        envelope: Envelope<T>,
+    ) -> Result<DeliveryReport> {
+        let key = envelope.request.partition_key().to_owned();
+        let payload = json::to_vec(&envelope.to_value()?)?;
+
+        self.send(
+            &self.topic,
+            &payload,
+            Some(key.as_bytes()),
+            tx_headers(workspace),
+        )
+        .await
+    }
+
+    /// Sends communication events together, so they are batched within [`KafkaProducerOpts::linger`].
+    ///
+    /// Reports are returned in the order of `envelopes`.
+    pub async fn send_events<T: Serialize + PartitionKeyProvider>(
+        &self,
+        workspace: WorkspaceUuid,
+        envelopes: impl IntoIterator<Item = Envelope<T>>,
+    ) -> Vec<Result<DeliveryReport>> {
+        futures::future::join_all(envelopes.into_iter().map(|envelope| async move {
+            let key = envelope.request.partition_key().to_owned();
+            let payload = json::to_vec(&envelope.to_value()?)?;
+
+            self.send(
+                &self.topic,
+                &payload,
+                Some(key.as_bytes()),
+                tx_headers(workspace),
+            )
+            .await
+        }))
+        .await
+    }
+
+    async fn send(
+        &self,
+        topic: &str,
+        payload: &[u8],
+        key: Option<&[u8]>,
+        headers: OwnedHeaders,
+    ) -> Result<DeliveryReport> {
+        let mut message = FutureRecord::to(topic).payload(payload).headers(headers);
+
+        if let Some(key) = key {
+            message = message.key(key)
+        }
+
+        let start = std::time::Instant::now();
+
+        let delivery = self
+            .producer
+            .send(message, Duration::from_secs(10))
+            .await
+            .map(DeliveryReport::from)
+            .map_err(|e| e.0.into());
+
+        metrics::kafka_delivery(topic, start.elapsed(), &delivery);
+
+        delivery
+    }
+
+    /// Sends `message` to this producer's (dead-letter) topic with its original headers and the error details
+    pub async fn dead_letter(
+        &self,
+        message: &impl Message,
+        error: &Error,
+        attempts: u32,
+    ) -> Result<()> {
+        let headers = dead_letter::headers(message, error, attempts);
+
+        self.send(
+            &self.topic,
+            message.payload().unwrap_or_default(),
+            message.key(),
+            headers,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Re-publishes messages from this producer's dead-letter topic to the topics they came from.
+    ///
+    /// Stops once no message arrives within `idle`, returns the number of replayed messages.
+    pub async fn replay_dead_letters(
+        &self,
+        config: &Config,
+        group_id: &str,
+        idle: Duration,
+    ) -> Result<usize> {
+        let consumer = consumer(config, group_id)?;
+        consumer.subscribe(&[&self.topic])?;
+
+        let mut replayed = 0;
+        while let Ok(message) = tokio::time::timeout(idle, consumer.recv()).await {
+            let message = message?;
+
+            match dead_letter::original(&message) {
+                Some((topic, headers)) => {
+                    self.send(
+                        &topic,
+                        message.payload().unwrap_or_default(),
+                        message.key(),
+                        headers,
+                    )
+                    .await?;
+                    replayed += 1;
+                }
+                None => {
+                    warn!(
+                        offset = message.offset(),
+                        "dead letter without original topic"
+                    );
+                }
+            }
+
+            consumer.commit_message(&message, CommitMode::Sync)?;
+        }
+
+        Ok(replayed)
+    }
+}
+
+/// Headers added to dead-lettered messages
+pub mod dead_letter {
+    use rdkafka::Message;
+    use rdkafka::message::{Header, Headers, OwnedHeaders};
+
+    use crate::Error;
+
+    pub const ERROR: &str = "DeadLetterError";
+    pub const ATTEMPTS: &str = "DeadLetterAttempts";
+    pub const TOPIC: &str = "DeadLetterTopic";
+    pub const PARTITION: &str = "DeadLetterPartition";
+    pub const OFFSET: &str = "DeadLetterOffset";
+
+    const ALL: [&str; 5] = [ERROR, ATTEMPTS, TOPIC, PARTITION, OFFSET];
+
+    fn copy(message: &impl Message, skip: &[&str]) -> OwnedHeaders {
+        let mut headers = OwnedHeaders::new();
+
+        if let Some(original) = message.headers() {
+            for header in original.iter().filter(|h| !skip.contains(&h.key)) {
+                headers = headers.insert(header);
+            }
+        }
+
+        headers
+    }
+
+    pub(super) fn headers(message: &impl Message, error: &Error, attempts: u32) -> OwnedHeaders {
+        copy(message, &ALL)
+            .insert(Header {
+                key: ERROR,
+                value: Some(&error.to_string()),
+            })
+            .insert(Header {
+                key: ATTEMPTS,
+                value: Some(&attempts.to_string()),
+            })
+            .insert(Header {
+                key: TOPIC,
+                value: Some(message.topic()),
+            })
+            .insert(Header {
+                key: PARTITION,
+                value: Some(&message.partition().to_string()),
+            })
+            .insert(Header {
+                key: OFFSET,
+                value: Some(&message.offset().to_string()),
+            })
+    }
+
+    /// Original topic and headers of a dead-lettered message
+    pub(super) fn original(message: &impl Message) -> Option<(String, OwnedHeaders)> {
+        let topic = message
+            .headers()?
+            .iter()
+            .find(|h| h.key == TOPIC)?
+            .value
+            .map(String::from_utf8_lossy)?
+            .into_owned();
+
+        Some((topic, copy(message, &ALL)))
+    }
+}
+
+/// Workspace of a transaction message, taken from its headers or, failing that, its key
+pub fn workspace_of(message: &impl Message) -> Result<WorkspaceUuid> {
+    let header = message.headers().and_then(|headers| {
+        WORKSPACE_HEADERS
+            .iter()
+            .find_map(|key| headers.iter().find(|h| h.key == *key))
+            .and_then(|header| header.value)
+    });
+
+    let workspace_id = header
+        .or(message.key())
+        .ok_or_else(|| Error::Other("NoWorkspaceId"))?;
+
+    std::str::from_utf8(workspace_id)
+        .ok()
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| Error::Other("InvalidWorkspaceId"))
+}
+
+/// Trace context the message was produced in, to continue the trace when handling it
+#[cfg(feature = "otel")]
+pub fn extract_trace_context<M: Message>(message: &M) -> opentelemetry::Context {
+    struct HeadersExtractor<'a, H>(Option<&'a H>);
+
+    impl<H: Headers> opentelemetry::propagation::Extractor for HeadersExtractor<'_, H> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0?
+                .iter()
+                .find(|h| h.key == key)?
+                .value
+                .and_then(|value| std::str::from_utf8(value).ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0
+                .map(|headers| headers.iter().map(|h| h.key).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    crate::services::otel::extract_trace_context(&HeadersExtractor(message.headers()))
+}
+
+pub fn parse_message(message: &impl Message) -> Result<(WorkspaceUuid, Value)> {
+    let payload = message
+        .payload()
+        .ok_or_else(|| Error::Other("MissingPayload"))?;
+    let payload = json::from_slice::<Value>(payload)?;
+
+    Ok((workspace_of(message)?, payload))
+}
+
+pub trait TransactionsConsumer {
+    #[deprecated = "use KafkaTxConsumer"]
+    fn tx_recv(&self) -> impl Future<Output = (WorkspaceUuid, Value)>;
+}
+
+impl<C: ConsumerContext + 'static, R> TransactionsConsumer for StreamConsumer<C, R> {
+    async fn tx_recv(&self) -> (WorkspaceUuid, Value) {
+        loop {
+            let transaction = self
+                .recv()
+                .await
+                .map_err(Error::from)
+                .and_then(|message| parse_message(&message));
+
+            match transaction {
+                Ok(transaction) => break transaction,
+                Err(error) => {
+                    warn!(%error, "transaction error");
+                }
+            }
+        }
+    }
+}
+
+/// Retries of a failed transaction handler
+#[derive(Debug, Clone, derive_builder::Builder)]
+pub struct RetryPolicy {
+    /// Attempts including the first one
+    #[builder(default = "3")]
+    pub max_attempts: u32,
+
+    #[builder(default = "Duration::from_millis(500)")]
+    pub initial_backoff: Duration,
+
+    #[builder(default = "Duration::from_secs(30)")]
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn builder() -> RetryPolicyBuilder {
+        RetryPolicyBuilder::default()
+    }
+
+    /// Delay after the given number of failed attempts, doubled each time
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build().expect("all fields have defaults")
+    }
+}
+
+fn consumer(config: &Config, group_id: &str) -> Result<StreamConsumer> {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", config.kafka_bootstrap_servers())
+        .set("group.id", group_id)
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "earliest");
+
+    if let Some(debug) = &config.kafka_rdkafka_debug {
+        client_config.set("debug", debug);
+    }
+
+    Ok(client_config.create()?)
+}
+
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// Returns `None` if the transaction is not meant for the handler
+type Handler = Box<dyn Fn(WorkspaceUuid, &Value) -> Option<Result<HandlerFuture>> + Send + Sync>;
+
+/// Consumes transactions from Kafka and dispatches them to registered handlers.
+///
+/// Offsets are committed only after all matching handlers succeed, so delivery is at-least-once.
+/// Failed handlers are retried according to [`RetryPolicy`], after that the message is sent to the
+/// dead-letter topic if one is configured, or delivered again otherwise.
+/// Messages which can't be decoded are dead-lettered, or logged and skipped.
+pub struct KafkaTxConsumer {
+    consumer: StreamConsumer,
+    handlers: Vec<Handler>,
+    retry: RetryPolicy,
+    dead_letter: Option<KafkaProducer>,
+}
+
+impl KafkaTxConsumer {
+    pub fn new(config: &Config, group_id: &str, topics: &[&str]) -> Result<Self> {
+        let consumer = consumer(config, group_id)?;
+        consumer.subscribe(topics)?;
+
+        Ok(Self {
+            consumer,
+            handlers: Vec::new(),
+            retry: RetryPolicy::default(),
+            dead_letter: None,
+        })
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Messages which can't be decoded or handled within [`RetryPolicy::max_attempts`] go to `producer`'s topic
+    pub fn with_dead_letter(mut self, producer: KafkaProducer) -> Self {
+        self.dead_letter = Some(producer);
+        self
+    }
+
+    /// Handles every transaction as raw json
+    pub fn on_tx<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(WorkspaceUuid, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.handlers.push(Box::new(move |workspace, value| {
+            Some(Ok(Box::pin(handler(workspace, value.clone()))))
+        }));
+        self
+    }
+
+    /// Handles transactions on documents of class `C`
+    pub fn on_event<C, F, Fut>(mut self, handler: F) -> Self
+    where
+        C: Class + DeserializeOwned + 'static,
+        F: Fn(WorkspaceUuid, TxEvent<C>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.handlers.push(Box::new(move |workspace, value| {
+            TxEvent::<C>::from_value(value).transpose().map(|event| {
+                event.map(|event| Box::pin(handler(workspace, event)) as HandlerFuture)
+            })
+        }));
+        self
+    }
+
+    /// Handles [`TxDomainEvent`]s carrying a [`DomainEvent`] of type `E`
+    pub fn on_domain_event<E, F, Fut>(mut self, handler: F) -> Self
+    where
+        E: DomainEvent + Debug + 'static,
+        F: Fn(WorkspaceUuid, TxDomainEvent<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.handlers.push(Box::new(move |workspace, value| {
+            is_domain_event::<E>(value).then(|| {
+                let event = TxDomainEvent::<E>::deserialize(value)?;
+                Ok(Box::pin(handler(workspace, event)) as HandlerFuture)
+            })
+        }));
+        self
+    }
+
+    /// Handles a message within a span continuing the trace it was produced in
+    async fn handle(&self, message: &BorrowedMessage<'_>) -> Result<()> {
+        let span = tracing::info_span!(
+            "kafka.consume",
+            topic = message.topic(),
+            partition = message.partition(),
+            offset = message.offset()
+        );
+
+        #[cfg(feature = "otel")]
+        {
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+            span.set_parent(extract_trace_context(message));
+        }
+
+        self.process(message).instrument(span).await
+    }
+
+    /// Runs the handlers, committing the offset on success and rewinding to the message otherwise
+    async fn process(&self, message: &BorrowedMessage<'_>) -> Result<()> {
+        let (workspace, value) = match parse_message(message) {
+            Ok(transaction) => transaction,
+            Err(error) => return self.reject(message, error, 0).await,
+        };
+
+        for handler in &self.handlers {
+            let mut attempts = 0;
+
+            loop {
+                let result = match handler(workspace, &value) {
+                    None => break,
+                    Some(Ok(future)) => future.await,
+                    Some(Err(error)) => return self.reject(message, error, 0).await,
+                };
+
+                let Err(error) = result else {
+                    break;
+                };
+
+                attempts += 1;
+                if attempts >= self.retry.max_attempts {
+                    return self.reject(message, error, attempts).await;
+                }
+
+                warn!(%error, attempts, "transaction handler failed, retrying");
+                tokio::time::sleep(self.retry.backoff(attempts)).await;
+            }
+        }
+
+        debug!(
+            topic = message.topic(),
+            offset = message.offset(),
+            "transaction handled"
+        );
+        self.consumer.commit_message(message, CommitMode::Async)?;
+
+        Ok(())
+    }
+
+    /// Gives up on a message, `attempts` is 0 if it couldn't be decoded
+    async fn reject(
+        &self,
+        message: &BorrowedMessage<'_>,
+        error: Error,
+        attempts: u32,
+    ) -> Result<()> {
+        match &self.dead_letter {
+            Some(producer) => {
+                if let Err(dead_letter_error) =
+                    producer.dead_letter(message, &error, attempts).await
+                {
+                    self.rewind(message)?;
+                    return Err(dead_letter_error);
+                }
+
+                warn!(%error, topic = message.topic(), offset = message.offset(), "transaction dead-lettered");
+            }
+            None if attempts == 0 => {
+                warn!(%error, topic = message.topic(), offset = message.offset(), "skipping malformed transaction");
+            }
+            None => {
+                self.rewind(message)?;
+                return Err(error);
+            }
+        }
+
+        self.consumer.commit_message(message, CommitMode::Async)?;
+
+        Ok(())
+    }
+
+    /// Seeks back to `message`, so that it is delivered again
+    fn rewind(&self, message: &BorrowedMessage<'_>) -> Result<()> {
+        self.consumer.seek(
+            message.topic(),
+            message.partition(),
+            Offset::Offset(message.offset()),
+            Duration::from_secs(10),
+        )?;
+
+        Ok(())
+    }
+
+    /// Receives and handles a single message
+    pub async fn recv(&self) -> Result<()> {
+        let message = self.consumer.recv().await?;
+        self.handle(&message).await
+    }
+
+    /// Handles messages until receiving from Kafka fails
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            let message = self.consumer.recv().await?;
+
+            if let Err(error) = self.handle(&message).await {
+                warn!(%error, "transaction handler failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::Timestamp;
+    use rdkafka::message::OwnedMessage;
+
+    use super::*;
+
+    fn message(key: Option<&str>, headers: &[(&str, &str)]) -> OwnedMessage {
+        let headers = headers.iter().fold(OwnedHeaders::new(), |h, (key, value)| {
+            h.insert(Header {
+                key,
+                value: Some(*value),
+            })
+        });
+
+        OwnedMessage::new(
+            Some(b"{}".to_vec()),
+            key.map(|k| k.as_bytes().to_vec()),
+            "tx".to_owned(),
+            Timestamp::NotAvailable,
+            0,
+            0,
+            Some(headers),
+        )
+    }
+
+    #[test]
+    fn test_workspace_of() {
+        let workspace = "d1b7ad79-2a3d-4bd5-9f49-8ef5bb5d4f0e";
+
+        let from_header = message(None, &[("workspace", workspace)]);
+        assert_eq!(workspace_of(&from_header).unwrap().to_string(), workspace);
+
+        let from_key = message(Some(workspace), &[("Mode", "transaction")]);
+        assert_eq!(workspace_of(&from_key).unwrap().to_string(), workspace);
+
+        let invalid = message(None, &[("WorkspaceUuid", "not-a-uuid")]);
+        assert!(workspace_of(&invalid).is_err());
+
+        assert!(workspace_of(&message(None, &[])).is_err());
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_extract_trace_context() {
+        use opentelemetry::trace::TraceContextExt;
+
+        let traced = message(
+            None,
+            &[(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )],
+        );
+
+        let context = extract_trace_context(&traced);
+        assert_eq!(
+            context.span().span_context().trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+
+        let untraced = extract_trace_context(&message(None, &[]));
+        assert!(!untraced.span().span_context().is_valid());
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let retry = RetryPolicy::builder()
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        assert_eq!(retry.backoff(1), Duration::from_secs(1));
+        assert_eq!(retry.backoff(2), Duration::from_secs(2));
+        assert_eq!(retry.backoff(3), Duration::from_secs(4));
+        assert_eq!(retry.backoff(4), Duration::from_secs(5));
+        assert_eq!(retry.backoff(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_dead_letter_headers() {
+        let original = message(None, &[("workspace", "ws"), ("Mode", "transaction")]);
+        let headers = dead_letter::headers(&original, &Error::Other("HandlerFailed"), 3);
+
+        let dead = OwnedMessage::new(
+            original.payload().map(<[u8]>::to_vec),
+            None,
+            "tx-dead".to_owned(),
+            Timestamp::NotAvailable,
+            0,
+            7,
+            Some(headers),
+        );
+
+        let header = |key| {
+            dead.headers()
+                .unwrap()
+                .iter()
+                .find(|h| h.key == key)
+                .and_then(|h| h.value)
+                .map(|v| String::from_utf8_lossy(v).into_owned())
+        };
+        assert_eq!(header(dead_letter::ERROR).as_deref(), Some("HandlerFailed"));
+        assert_eq!(header(dead_letter::ATTEMPTS).as_deref(), Some("3"));
+        assert_eq!(header(dead_letter::TOPIC).as_deref(), Some("tx"));
+        assert_eq!(header("workspace").as_deref(), Some("ws"));
+
+        let (topic, headers) = dead_letter::original(&dead).unwrap();
+        assert_eq!(topic, "tx");
+        assert_eq!(
+            headers.iter().map(|h| h.key).collect::<Vec<_>>(),
+            ["workspace", "Mode"]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 macro_rules! api_methods {
     ($($Variant:ident: $kebab:literal, $camel:literal),+ $(,)?) => {
-        #[derive(Debug, Clone, Copy)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
         pub enum Method { $($Variant),+ }
 
         impl Method {
@@ -15,6 +15,20 @@ macro_rules! api_methods {
                     $( Self::$Variant => $camel ),+
                 }
             }
+
+            pub fn from_kebab(kebab: &str) -> Option<Self> {
+                match kebab {
+                    $( $kebab => Some(Self::$Variant), )+
+                    _ => None,
+                }
+            }
+
+            pub fn from_camel(camel: &str) -> Option<Self> {
+                match camel {
+                    $( $camel => Some(Self::$Variant), )+
+                    _ => None,
+                }
+            }
         }
 
         impl std::fmt::Display for Method {
@@ -19,21 +19,29 @@ use crate::services::core::classes::OperationDomain;
 use crate::services::core::storage::DomainResult;
 use crate::services::event::{Class, DocT};
 use crate::services::transactor::backend::Backend;
+use crate::services::transactor::backend::SubscriptionBackend;
 use crate::services::transactor::backend::http::{HttpBackend, HttpClient};
+#[cfg(feature = "testing")]
+use crate::services::transactor::backend::memory::MemoryBackend;
 use crate::services::transactor::backend::ws::{WsBackend, WsBackendOpts};
 use crate::services::transactor::document::{FindOptions, RemoveDocument};
 use crate::services::transactor::methods::Method;
 use crate::services::transactor::subscription::LiveQueryEvent;
+#[cfg(feature = "testing")]
+use crate::testing::{RecordingBackend, ReplayBackend};
 use futures::Stream;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
-use subscription::SubscribedQuery;
+use subscription::{SubscribedDomainEvents, SubscribedQuery, SubscribedWorkspaceEvents};
 use url::Url;
 
 pub mod backend;
+pub mod card;
 pub mod comm;
 pub mod document;
+#[cfg(feature = "kafka")]
+pub mod kafka;
 pub mod methods;
 pub mod person;
 pub mod subscription;
@@ -188,7 +196,9 @@ impl<B: Backend> TransactorClient<B> {
             .build()
             .expect("fields filled");
 
-        self.tx(tx).await
+        self.tx::<_, Value>(tx).await?;
+
+        Ok(())
     }
 }
 
@@ -219,174 +229,67 @@ impl TransactorClient<WsBackend> {
 
         Ok(Self { backend })
     }
+}
 
-    pub async fn subscribe<T: crate::services::event::Event + DeserializeOwned>(
-        &self,
-    ) -> SubscribedQuery<T> {
-        SubscribedQuery::new(self.clone())
+#[cfg(feature = "testing")]
+impl TransactorClient<MemoryBackend> {
+    /// Client over an in-memory workspace, keep a clone of `backend` to seed or inspect it
+    pub fn new_memory(backend: MemoryBackend) -> Self {
+        Self { backend }
     }
+}
 
-    /// Fetches all documents of the specified [`Class`], and subscribes to future events
-    pub fn live_query<C: Class + DeserializeOwned + Send + Unpin + 'static, Q: Serialize + Send>(
-        &self,
-        query: Q,
-        options: FindOptions,
-    ) -> impl Stream<Item = Result<LiveQueryEvent<C>>> + Send + use<C, Q> {
-        subscription::live_query(self.clone(), query, options)
+#[cfg(feature = "testing")]
+impl<B: Backend> TransactorClient<B> {
+    /// Records the traffic of this client, see [`RecordingBackend`]
+    pub fn into_recording(self) -> TransactorClient<RecordingBackend<B>> {
+        TransactorClient {
+            backend: RecordingBackend::new(self.backend),
+        }
     }
 }
 
-#[cfg(feature = "kafka")]
-pub mod kafka {
-    use super::*;
-    use crate::{Config, Error, services::core::WorkspaceUuid};
-    use rdkafka::{
-        ClientConfig, Message,
-        consumer::{ConsumerContext, StreamConsumer},
-        message::{Header, Headers, OwnedHeaders},
-        producer::FutureProducer,
-    };
-    use serde_json::{self as json, Value};
-    use std::time::Duration;
-    use tracing::{debug, warn};
-    use uuid::Uuid;
-
-    pub struct KafkaProducer {
-        producer: FutureProducer,
-        topic: String,
+#[cfg(feature = "testing")]
+impl<B: Backend> TransactorClient<RecordingBackend<B>> {
+    pub fn recorder(&self) -> &RecordingBackend<B> {
+        &self.backend
     }
+}
 
-    impl KafkaProducer {
-        pub fn new(config: &Config, topic: &str) -> Result<Self> {
-            let producer = ClientConfig::new()
-                .set(
-                    "bootstrap.servers",
-                    config.kafka_bootstrap_servers.join(","),
-                )
-                .set("message.timeout.ms", "5000")
-                .create()?;
-
-            Ok(Self {
-                producer,
-                topic: topic.to_owned(),
-            })
-        }
-
-        pub async fn tx_raw<T: Serialize>(
-            &self,
-            workspace: WorkspaceUuid,
-            transaction: T,
-            partition_key: Option<&str>,
-        ) -> Result<()> {
-            let payload = json::to_vec(&transaction)?;
-
-            let headers = OwnedHeaders::new()
-                .insert(Header {
-                    key: "WorkspaceUuid",
-                    value: Some(&workspace.to_string()),
-                })
-                .insert(Header {
-                    key: "workspace",
-                    value: Some(&workspace.to_string()),
-                })
-                .insert(Header {
-                    key: "Mode",
-                    value: Some("transaction"),
-                });
-
-            let mut message = rdkafka::producer::FutureRecord::to(&self.topic)
-                .payload(&payload)
-                .headers(headers);
-
-            if let Some(partition_key) = partition_key {
-                message = message.key(partition_key)
-            }
-
-            self.producer
-                .send(message, Duration::from_secs(10))
-                .await
-                .map_err(|e| e.0)?;
-
-            Ok(())
-        }
+#[cfg(feature = "testing")]
+impl TransactorClient<ReplayBackend> {
+    pub fn new_replay(backend: ReplayBackend) -> Self {
+        Self { backend }
+    }
+}
 
-        pub async fn tx<T: Transaction>(
-            &self,
-            workspace: WorkspaceUuid,
-            tx: T,
-            partition_key: Option<&str>,
-        ) -> Result<()> {
-            self.tx_raw(workspace, tx.to_value()?, partition_key).await
-        }
+impl<B: SubscriptionBackend> TransactorClient<B> {
+    pub async fn subscribe<T: crate::services::event::Event + DeserializeOwned>(
+        &self,
+    ) -> SubscribedQuery<T> {
+        SubscribedQuery::new(self.clone())
     }
 
-    pub trait TransactionsConsumer {
-        fn tx_recv(&self) -> impl Future<Output = (WorkspaceUuid, Value)>;
+    /// Subscribes to domain events of type `E`, e.g. [`comm::MessageEvent`]
+    pub fn subscribe_domain_events<E: crate::services::event::DomainEvent>(
+        &self,
+    ) -> SubscribedDomainEvents<E> {
+        SubscribedDomainEvents::new(self.clone())
     }
 
-    impl<C: ConsumerContext + 'static, R> TransactionsConsumer for StreamConsumer<C, R> {
-        async fn tx_recv(&self) -> (WorkspaceUuid, Value) {
-            fn inner(message: Option<impl Message>) -> Result<(WorkspaceUuid, Value)> {
-                let message = message.ok_or_else(|| Error::Other("KafkaError"))?;
-
-                let payload = message
-                    .payload()
-                    .ok_or_else(|| Error::Other("MissingPayload"))?;
-                let payload = json::from_slice::<Value>(payload)?;
-
-                let workspace_id = message
-                    .headers()
-                    .and_then(|headers| {
-                        headers
-                            .iter()
-                            .find(|h| h.key == "workspace" || h.key == "WorkspaceUuid")
-                    })
-                    .and_then(|header| header.value)
-                    .map(String::from_utf8_lossy);
-
-                debug!(?workspace_id, "workspace id");
-
-                let workspace_id = workspace_id
-                    .map(|s| Uuid::from_slice(s.as_bytes()))
-                    .ok_or_else(|| Error::Other("NoWorkspaceId"))?
-                    .map_err(|_| Error::Other("InvalidWorkspaceId"))?;
-
-                Ok((workspace_id, payload))
-            }
-
-            loop {
-                let message = self.recv().await.ok();
-
-                println!("{message:?}");
-
-                match inner(message) {
-                    Ok(transaction) => break transaction,
-                    Err(error) => {
-                        warn!(%error, "transaction error");
-                    }
-                }
-            }
-        }
+    /// Subscribes to workspace events, e.g. scheduled upgrades and maintenance notifications
+    pub fn subscribe_workspace_events(&self) -> SubscribedWorkspaceEvents {
+        SubscribedWorkspaceEvents::new(self.clone())
     }
+}
 
-    pub fn parse_message(message: &impl Message) -> Result<(WorkspaceUuid, Value)> {
-        let payload = message
-            .payload()
-            .ok_or_else(|| Error::Other("MissingPayload"))?;
-        let payload = json::from_slice::<Value>(payload)?;
-
-        let workspace_id = message
-            .headers()
-            .and_then(|headers| headers.iter().find(|h| h.key == "WorkspaceUuid"))
-            .and_then(|header| header.value)
-            .or(message.key())
-            .map(String::from_utf8_lossy);
-
-        let workspace_id = workspace_id
-            .map(|s| Uuid::parse_str(&s))
-            .ok_or_else(|| Error::Other("NoWorkspaceId"))?
-            .map_err(|_| Error::Other("InvalidWorkspaceId"))?;
-
-        Ok((workspace_id, payload))
+impl<B: SubscriptionBackend + Send + Sync + 'static> TransactorClient<B> {
+    /// Fetches all documents of the specified [`Class`], and subscribes to future events
+    pub fn live_query<C: Class + DeserializeOwned + Send + Unpin + 'static, Q: Serialize + Send>(
+        &self,
+        query: Q,
+        options: FindOptions,
+    ) -> impl Stream<Item = Result<LiveQueryEvent<C>>> + use<B, C, Q> {
+        subscription::live_query(self.clone(), query, options)
     }
 }
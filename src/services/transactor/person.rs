@@ -18,7 +18,10 @@ use serde::{Deserialize, Serialize};
 use crate::services::core::{PersonId, PersonUuid};
 use crate::services::transactor::backend::Backend;
 use crate::services::transactor::methods::Method;
-use crate::{Result, services::core::SocialIdType};
+use crate::{
+    Result,
+    services::core::{SocialIdType, SocialKey},
+};
 
 #[derive(Serialize, Debug, derive_builder::Builder)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +37,14 @@ pub struct EnsurePersonRequest {
     pub last_name: Option<String>,
 }
 
+impl EnsurePersonRequestBuilder {
+    /// Sets the social type and normalized value
+    pub fn social_key(&mut self, key: SocialKey) -> &mut Self {
+        let (social_type, social_value) = key.into_parts();
+        self.social_type(social_type).social_value(social_value)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct EnsurePersonResponse {
@@ -1,14 +1,18 @@
+use crate::services::core::class;
 use crate::services::core::storage::WithLookup;
-use crate::services::core::tx::{TxCreateDoc, TxRemoveDoc, TxUpdateDoc};
-use crate::services::event::{Class, Event};
+use crate::services::core::tx::{
+    TxCreateDoc, TxDomainEvent, TxRemoveDoc, TxUpdateDoc, TxWorkspaceEvent, WorkspaceEventParams,
+};
+use crate::services::event::{Class, DomainEvent, Event};
+use crate::services::metrics;
 use crate::services::transactor::TransactorClient;
-use crate::services::transactor::backend::ws::WsBackend;
+use crate::services::transactor::backend::SubscriptionBackend;
 use crate::services::transactor::document::{DocumentClient, FindOptions};
 use crate::{Error, Result};
 use futures::StreamExt;
 use futures::{Stream, TryStreamExt};
-use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -23,7 +27,7 @@ pub struct SubscribedQuery<C: Class> {
 }
 
 impl<C: Class> SubscribedQuery<C> {
-    pub fn new(client: TransactorClient<WsBackend>) -> Self {
+    pub fn new<B: SubscriptionBackend>(client: TransactorClient<B>) -> Self {
         let tx_rx = client.backend().tx_stream();
 
         Self {
@@ -40,6 +44,26 @@ pub enum TxEvent<C> {
     Deleted(Box<TxRemoveDoc>),
 }
 
+impl<C: Class + DeserializeOwned> TxEvent<C> {
+    /// Parses a raw transaction, returns `None` if it doesn't concern documents of class `C`
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        if TxCreateDoc::<C>::matches(value) {
+            let tx = TxCreateDoc::<C>::deserialize(value)?;
+            Ok(Some(TxEvent::Created(Box::new(tx))))
+        } else if TxUpdateDoc::<C>::matches(value) {
+            let tx = TxUpdateDoc::<C>::deserialize(value)?;
+            Ok(Some(TxEvent::Updated(Box::new(tx))))
+        } else if TxRemoveDoc::matches(value)
+            && value.get("objectClass").and_then(|v| v.as_str()) == Some(C::CLASS)
+        {
+            let tx = TxRemoveDoc::deserialize(value)?;
+            Ok(Some(TxEvent::Deleted(Box::new(tx))))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl<T> TxEvent<WithLookup<T>> {
     pub fn strip_lookup(self) -> TxEvent<T> {
         match self {
@@ -65,22 +89,105 @@ impl<C: Class + DeserializeOwned + Send + Unpin + 'static> Stream for Subscribed
         loop {
             match self.tx_rx.try_poll_next_unpin(cx) {
                 Poll::Ready(Some(Ok(value))) => {
-                    if TxCreateDoc::<C>::matches(&value) {
-                        let tx: TxCreateDoc<C> = serde_json::from_value(value)?;
-                        return Poll::Ready(Some(Ok(TxEvent::Created(Box::new(tx)))));
-                    } else if TxUpdateDoc::<C>::matches(&value) {
-                        let tx: TxUpdateDoc<C> = serde_json::from_value(value)?;
-                        return Poll::Ready(Some(Ok(TxEvent::Updated(Box::new(tx)))));
-                    } else if TxRemoveDoc::matches(&value)
-                        && value.get("objectClass").and_then(|v| v.as_str()) == Some(C::CLASS)
+                    if let Some(event) = TxEvent::<C>::from_value(&value).transpose() {
+                        return Poll::Ready(Some(event));
+                    }
+
+                    continue;
+                }
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(missed)))) => {
+                    metrics::broadcast_lagged(missed);
+                    return Poll::Ready(Some(Err(Error::SubscriptionLagged)));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+/// Whether `value` is a [`TxDomainEvent`] carrying a [`DomainEvent`] of type `E`
+pub fn is_domain_event<E: DomainEvent + Debug>(value: &Value) -> bool {
+    TxDomainEvent::<E>::matches(value)
+        && value.get("domain").and_then(|v| v.as_str()) == Some(E::DOMAIN)
+        && value.get("event").is_some_and(E::matches)
+}
+
+/// Stream of [`TxDomainEvent`]s whose payload is a [`DomainEvent`] of type `E`
+pub struct SubscribedDomainEvents<E> {
+    tx_rx: BroadcastStream<Value>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: DomainEvent> SubscribedDomainEvents<E> {
+    pub fn new<B: SubscriptionBackend>(client: TransactorClient<B>) -> Self {
+        let tx_rx = client.backend().tx_stream();
+
+        Self {
+            tx_rx,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: DomainEvent + Debug + Unpin> Stream for SubscribedDomainEvents<E> {
+    type Item = Result<TxDomainEvent<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.tx_rx.try_poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(value))) => {
+                    if is_domain_event::<E>(&value) {
+                        let tx: TxDomainEvent<E> = serde_json::from_value(value)?;
+                        return Poll::Ready(Some(Ok(tx)));
+                    }
+
+                    continue;
+                }
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(missed)))) => {
+                    metrics::broadcast_lagged(missed);
+                    return Poll::Ready(Some(Err(Error::SubscriptionLagged)));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+pub struct SubscribedWorkspaceEvents {
+    tx_rx: BroadcastStream<Value>,
+}
+
+impl SubscribedWorkspaceEvents {
+    pub fn new<B: SubscriptionBackend>(client: TransactorClient<B>) -> Self {
+        Self {
+            tx_rx: client.backend().tx_stream(),
+        }
+    }
+}
+
+impl Stream for SubscribedWorkspaceEvents {
+    type Item = Result<TxWorkspaceEvent<WorkspaceEventParams>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.tx_rx.try_poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(value))) => {
+                    if value.get("_class").and_then(|v| v.as_str()) == Some(class::TxWorkspaceEvent)
                     {
-                        let tx: TxRemoveDoc = serde_json::from_value(value)?;
-                        return Poll::Ready(Some(Ok(TxEvent::Deleted(Box::new(tx)))));
+                        let tx: TxWorkspaceEvent<Value> = serde_json::from_value(value)?;
+                        return Poll::Ready(Some(Ok(tx.try_into()?)));
                     }
 
                     continue;
                 }
-                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(_)))) => {
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(missed)))) => {
+                    metrics::broadcast_lagged(missed);
                     return Poll::Ready(Some(Err(Error::SubscriptionLagged)));
                 }
                 Poll::Ready(None) => return Poll::Ready(None),
@@ -110,13 +217,14 @@ impl<T> LiveQueryEvent<WithLookup<T>> {
 }
 
 pub(super) fn live_query<
+    B: SubscriptionBackend,
     C: Class + Debug + DeserializeOwned + Send + Unpin + 'static,
     Q: Serialize + Send,
 >(
-    client: TransactorClient<WsBackend>,
+    client: TransactorClient<B>,
     query: Q,
     options: FindOptions,
-) -> impl Stream<Item = Result<LiveQueryEvent<C>>> + Send {
+) -> impl Stream<Item = Result<LiveQueryEvent<C>>> {
     let client_clone = client.clone();
     let initial_fetch = async move {
         let results = client_clone
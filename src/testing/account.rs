@@ -0,0 +1,714 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Json, State};
+use axum::http::HeaderMap;
+use axum::routing::post;
+use axum::{Router, response::IntoResponse};
+use chrono::Utc;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use url::Url;
+
+use super::{Server, TOKEN_SECRET, bearer_claims, status};
+use crate::services::Status;
+use crate::services::account::{
+    AccountSocialId, EnsurePersonParams, EnsurePersonResult, Integration, IntegrationKey,
+    IntegrationSecret, IntegrationSecretKey, ListAccountsParams, LoginInfo, PartialIntegrationKey,
+    SelectWorkspaceParams, WorkspaceInfo, WorkspaceInfoWithStatus, WorkspaceLoginInfo,
+    WorkspaceMode, WorkspaceStatus, WorkspaceVersion,
+};
+use crate::services::core::{AccountRole, SocialId, SocialKey, WorkspaceDataId, WorkspaceUuid};
+use crate::services::jwt::Claims;
+use crate::{Config, ConfigBuilder, Result};
+
+/// Workspace [`MockAccount`] lets any account select
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceFixture {
+    pub uuid: WorkspaceUuid,
+    pub url: String,
+
+    #[serde(default)]
+    pub data_id: Option<WorkspaceDataId>,
+
+    /// Transactor returned as the workspace endpoint
+    pub endpoint: Url,
+
+    #[serde(default = "default_role")]
+    pub role: AccountRole,
+
+    /// Status reported by `getWorkspacesInfo`, active when absent
+    #[serde(default)]
+    pub status: Option<WorkspaceStatus>,
+}
+
+fn default_role() -> AccountRole {
+    AccountRole::OWNER
+}
+
+fn active_status() -> WorkspaceStatus {
+    WorkspaceStatus {
+        version: WorkspaceVersion {
+            version_major: 0,
+            version_minor: 7,
+            version_patch: 0,
+        },
+        mode: Some(WorkspaceMode::Active),
+        processing_progress: Some(100),
+        last_processing_time: None,
+        last_visit: None,
+        is_disabled: Some(false),
+        processing_attempts: Some(0),
+        processing_message: None,
+        backup_info: None,
+        target_region: None,
+    }
+}
+
+/// Data [`MockAccount`] is seeded with
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccountFixture {
+    pub workspaces: Vec<WorkspaceFixture>,
+    pub social_ids: Vec<AccountSocialId>,
+    pub integrations: Vec<Integration>,
+    pub secrets: Vec<IntegrationSecret>,
+}
+
+impl AccountFixture {
+    /// Reads a fixture from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+}
+
+/// Account service speaking JSON-RPC on a random localhost port.
+///
+/// Requests must carry a token signed with [`TOKEN_SECRET`], the account in its claims is the caller.
+pub struct MockAccount {
+    server: Server,
+    state: Arc<Mutex<AccountFixture>>,
+}
+
+impl MockAccount {
+    pub async fn start(fixture: AccountFixture) -> Result<Self> {
+        let state = Arc::new(Mutex::new(fixture));
+
+        let router = Router::new()
+            .route("/", post(rpc))
+            .with_state(state.clone());
+
+        Ok(Self {
+            server: Server::start(router).await?,
+            state,
+        })
+    }
+
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/", self.server.addr)).expect("valid url")
+    }
+
+    /// Config pointing `account_service` at this server
+    pub fn config(&self) -> Config {
+        ConfigBuilder::default()
+            .token_secret(TOKEN_SECRET)
+            .account_service(self.url())
+            .build()
+            .expect("complete config")
+    }
+
+    /// Current state, including persons, integrations and secrets created by clients
+    pub fn snapshot(&self) -> AccountFixture {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    method: String,
+
+    #[serde(default)]
+    params: Value,
+}
+
+async fn rpc(
+    State(state): State<Arc<Mutex<AccountFixture>>>,
+    headers: HeaderMap,
+    Json(request): Json<Request>,
+) -> impl IntoResponse {
+    let reply = match bearer_claims(&headers) {
+        Some(claims) => {
+            let mut fixture = state.lock().unwrap();
+            dispatch(&mut fixture, &claims, &request.method, request.params)
+        }
+
+        None => Err(status("platform:status:Unauthorized")),
+    };
+
+    Json(match reply {
+        Ok(result) => json!({ "result": result }),
+        Err(error) => json!({ "error": error }),
+    })
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> std::result::Result<T, Status> {
+    serde_json::from_value(params).map_err(|_| status("platform:status:BadRequest"))
+}
+
+fn integration_matches(integration: &Integration, key: &IntegrationKey) -> bool {
+    integration.social_id == key.social_id
+        && integration.kind == key.kind
+        && integration.workspace_uuid == key.workspace_uuid
+}
+
+fn secret_matches(secret: &IntegrationSecret, key: &IntegrationSecretKey) -> bool {
+    secret.social_id == key.social_id
+        && secret.kind == key.kind
+        && secret.workspace_uuid == key.workspace_uuid
+        && secret.key == key.key
+}
+
+fn integration_key(integration: &Integration) -> IntegrationKey {
+    IntegrationKey {
+        social_id: integration.social_id.clone(),
+        kind: integration.kind.clone(),
+        workspace_uuid: integration.workspace_uuid,
+    }
+}
+
+fn secret_key(secret: &IntegrationSecret) -> IntegrationSecretKey {
+    IntegrationSecretKey {
+        social_id: secret.social_id.clone(),
+        kind: secret.kind.clone(),
+        workspace_uuid: secret.workspace_uuid,
+        key: secret.key.clone(),
+    }
+}
+
+fn dispatch(
+    fixture: &mut AccountFixture,
+    claims: &Claims,
+    method: &str,
+    params_value: Value,
+) -> std::result::Result<Value, Status> {
+    let account = claims.account();
+
+    let result = match method {
+        "selectWorkspace" => {
+            let params: SelectWorkspaceParams = params(params_value)?;
+
+            let workspace = fixture
+                .workspaces
+                .iter()
+                .find(|workspace| workspace.url == params.workspace_url)
+                .ok_or_else(|| {
+                    let mut status = status("platform:status:WorkspaceNotFound");
+                    status
+                        .params
+                        .insert("workspaceUrl".into(), params.workspace_url.clone().into());
+                    status
+                })?;
+
+            let claims = Claims {
+                account,
+                workspace: Some(workspace.uuid),
+                ..Default::default()
+            };
+
+            let token = claims
+                .encode(&TOKEN_SECRET.into())
+                .map(|token| token.expose_secret().to_owned())
+                .ok();
+
+            let social_id = fixture
+                .social_ids
+                .iter()
+                .find(|social_id| social_id.person_uuid == account && !social_id.is_deleted)
+                .map(|social_id| social_id.base.id.clone());
+
+            json!(WorkspaceLoginInfo {
+                base: LoginInfo {
+                    account,
+                    name: None,
+                    social_id,
+                    token,
+                },
+                workspace: workspace.uuid,
+                workspace_url: Some(workspace.url.clone()),
+                workspace_data_id: workspace.data_id.clone(),
+                endpoint: workspace.endpoint.clone(),
+                role: serde_json::to_value(workspace.role)
+                    .ok()
+                    .and_then(|role| role.as_str().map(ToOwned::to_owned))
+                    .unwrap_or_default(),
+            })
+        }
+
+        "ensurePerson" => {
+            let params: EnsurePersonParams = params(params_value)?;
+            let key = SocialKey::new(params.social_type, &params.social_value);
+
+            let existing = fixture
+                .social_ids
+                .iter()
+                .find(|social_id| social_id.base.social_key() == key);
+
+            let result = match existing {
+                Some(social_id) => EnsurePersonResult {
+                    uuid: social_id.person_uuid,
+                    social_id: social_id.base.id.clone(),
+                },
+
+                None => {
+                    let person = uuid::Builder::from_random_bytes(rand::random()).into_uuid();
+                    let id = rand::random::<u64>().to_string();
+                    fixture.social_ids.push(AccountSocialId {
+                        base: SocialId {
+                            id: id.clone(),
+                            key: key.to_string(),
+                            r#type: key.r#type().clone(),
+                            value: key.value().to_owned(),
+                            display_value: None,
+                            created_on: Some(Utc::now()),
+                            verified_on: None,
+                        },
+                        person_uuid: person,
+                        is_deleted: false,
+                    });
+
+                    EnsurePersonResult {
+                        uuid: person,
+                        social_id: id,
+                    }
+                }
+            };
+
+            json!(result)
+        }
+
+        "getWorkspacesInfo" => {
+            let ids: Vec<WorkspaceUuid> = params(params_value["ids"].clone())?;
+
+            let workspaces = fixture
+                .workspaces
+                .iter()
+                .filter(|workspace| ids.contains(&workspace.uuid))
+                .map(|workspace| WorkspaceInfoWithStatus {
+                    workspace: WorkspaceInfo {
+                        uuid: workspace.uuid,
+                        data_id: workspace.data_id.clone(),
+                        url: workspace.url.clone(),
+                        ..Default::default()
+                    },
+                    status: workspace.status.clone().unwrap_or_else(active_status),
+                })
+                .collect::<Vec<_>>();
+
+            json!(workspaces)
+        }
+
+        "getSocialIds" => {
+            let confirmed = params_value["confirmed"].as_bool().unwrap_or(false);
+
+            let social_ids = fixture
+                .social_ids
+                .iter()
+                .filter(|social_id| social_id.person_uuid == account && !social_id.is_deleted)
+                .filter(|social_id| !confirmed || social_id.base.verified_on.is_some())
+                .collect::<Vec<_>>();
+
+            json!(social_ids)
+        }
+
+        "listAccounts" => {
+            let params: ListAccountsParams = params(params_value)?;
+            let search = params.search.unwrap_or_default().to_lowercase();
+
+            let mut accounts = BTreeMap::<_, Vec<SocialId>>::new();
+
+            for social_id in fixture.social_ids.iter().filter(|id| !id.is_deleted) {
+                accounts
+                    .entry(social_id.person_uuid)
+                    .or_default()
+                    .push(social_id.base.clone());
+            }
+
+            let accounts = accounts
+                .into_iter()
+                .filter(|(_, social_ids)| {
+                    social_ids
+                        .iter()
+                        .any(|social_id| social_id.value.to_lowercase().contains(&search))
+                })
+                .skip(params.skip.unwrap_or_default() as usize)
+                .take(params.limit.map_or(usize::MAX, |limit| limit as usize))
+                .map(|(uuid, social_ids)| {
+                    json!({
+                        "uuid": uuid,
+                        "locale": null,
+                        "timezone": null,
+                        "socialIds": social_ids,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            json!(accounts)
+        }
+
+        "listIntegrations" => {
+            let key: PartialIntegrationKey = params(params_value)?;
+
+            let integrations = fixture
+                .integrations
+                .iter()
+                .filter(|integration| {
+                    key.social_id
+                        .as_ref()
+                        .is_none_or(|social_id| integration.social_id == *social_id)
+                        && key
+                            .kind
+                            .as_ref()
+                            .is_none_or(|kind| integration.kind == *kind)
+                        && key
+                            .workspace_uuid
+                            .is_none_or(|workspace| integration.workspace_uuid == workspace)
+                })
+                .collect::<Vec<_>>();
+
+            json!(integrations)
+        }
+
+        "getIntegration" => {
+            let key: IntegrationKey = params(params_value)?;
+
+            json!(
+                fixture
+                    .integrations
+                    .iter()
+                    .find(|integration| integration_matches(integration, &key))
+            )
+        }
+
+        "createIntegration" => {
+            let integration: Integration = params(params_value)?;
+            let key = integration_key(&integration);
+
+            if fixture
+                .integrations
+                .iter()
+                .any(|existing| integration_matches(existing, &key))
+            {
+                return Err(status("platform:status:IntegrationAlreadyExists"));
+            }
+
+            fixture.integrations.push(integration);
+            Value::Null
+        }
+
+        "updateIntegration" => {
+            let integration: Integration = params(params_value)?;
+            let key = integration_key(&integration);
+
+            let existing = fixture
+                .integrations
+                .iter_mut()
+                .find(|existing| integration_matches(existing, &key))
+                .ok_or_else(|| status("platform:status:IntegrationNotFound"))?;
+
+            *existing = integration;
+            Value::Null
+        }
+
+        "deleteIntegration" => {
+            let key: IntegrationKey = params(params_value)?;
+
+            let len = fixture.integrations.len();
+            fixture
+                .integrations
+                .retain(|integration| !integration_matches(integration, &key));
+
+            if fixture.integrations.len() == len {
+                return Err(status("platform:status:IntegrationNotFound"));
+            }
+
+            fixture.secrets.retain(|secret| {
+                !(secret.social_id == key.social_id
+                    && secret.kind == key.kind
+                    && secret.workspace_uuid == key.workspace_uuid)
+            });
+            Value::Null
+        }
+
+        "addIntegrationSecret" => {
+            let secret: IntegrationSecret = params(params_value)?;
+            let key = secret_key(&secret);
+
+            if fixture
+                .secrets
+                .iter()
+                .any(|existing| secret_matches(existing, &key))
+            {
+                return Err(status("platform:status:IntegrationSecretAlreadyExists"));
+            }
+
+            fixture.secrets.push(secret);
+            Value::Null
+        }
+
+        "getIntegrationSecret" => {
+            let key: IntegrationSecretKey = params(params_value)?;
+
+            json!(
+                fixture
+                    .secrets
+                    .iter()
+                    .find(|secret| secret_matches(secret, &key))
+            )
+        }
+
+        "updateIntegrationSecret" => {
+            let secret: IntegrationSecret = params(params_value)?;
+            let key = secret_key(&secret);
+
+            let existing = fixture
+                .secrets
+                .iter_mut()
+                .find(|existing| secret_matches(existing, &key))
+                .ok_or_else(|| status("platform:status:IntegrationSecretNotFound"))?;
+
+            *existing = secret;
+            Value::Null
+        }
+
+        "deleteIntegrationSecret" => {
+            let key: IntegrationSecretKey = params(params_value)?;
+
+            let len = fixture.secrets.len();
+            fixture
+                .secrets
+                .retain(|secret| !secret_matches(secret, &key));
+
+            if fixture.secrets.len() == len {
+                return Err(status("platform:status:IntegrationSecretNotFound"));
+            }
+
+            Value::Null
+        }
+
+        _ => {
+            let mut status = status("platform:status:UnknownMethod");
+            status.params.insert("method".into(), method.into());
+            return Err(status);
+        }
+    };
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceFactory;
+    use crate::services::account::DEFAULT_MAX_PROCESSING_ATTEMPTS;
+    use crate::{Error, services::account::EnsurePersonParamsBuilder};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_mock_account() {
+        let workspace = uuid::Uuid::from_u128(1);
+
+        let fixture = AccountFixture {
+            workspaces: vec![WorkspaceFixture {
+                uuid: workspace,
+                url: String::from("test"),
+                data_id: None,
+                endpoint: Url::parse("ws://localhost:3333/").unwrap(),
+                role: AccountRole::OWNER,
+                status: None,
+            }],
+            ..Default::default()
+        };
+
+        let mock = MockAccount::start(fixture).await.unwrap();
+        let factory = ServiceFactory::new(mock.config());
+
+        let ensured = factory
+            .new_account_client(&Claims::default())
+            .unwrap()
+            .ensure_person(
+                &EnsurePersonParamsBuilder::default()
+                    .social_key(SocialKey::email("User@Example.com"))
+                    .first_name("User")
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let claims = Claims {
+            account: ensured.uuid,
+            ..Default::default()
+        };
+        let client = factory.new_account_client(&claims).unwrap();
+
+        let login = client
+            .select_workspace(&SelectWorkspaceParams {
+                workspace_url: String::from("test"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(login.workspace, workspace);
+        assert_eq!(login.role, "OWNER");
+        assert_eq!(login.base.social_id.as_ref(), Some(&ensured.social_id));
+
+        let accounts = client
+            .list_accounts_stream(Some("example.com"))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].as_ref().unwrap().uuid, ensured.uuid);
+
+        let social_ids = client.get_social_ids(false).await.unwrap();
+        assert_eq!(social_ids[0].base.key, "email:user@example.com");
+        assert!(client.get_social_ids(true).await.unwrap().is_empty());
+
+        let integration = Integration {
+            social_id: ensured.social_id.clone(),
+            kind: String::from("github"),
+            workspace_uuid: Some(workspace),
+            data: None,
+        };
+        client.create_integration(&integration).await.unwrap();
+        assert!(matches!(
+            client.create_integration(&integration).await,
+            Err(Error::ServiceError(status)) if status.code == "platform:status:IntegrationAlreadyExists"
+        ));
+
+        let listed = client
+            .list_integrations(&PartialIntegrationKey {
+                workspace_uuid: Some(Some(workspace)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+
+        let secret = IntegrationSecret {
+            social_id: ensured.social_id.clone(),
+            kind: String::from("github"),
+            workspace_uuid: Some(workspace),
+            key: String::from("token"),
+            secret: String::from("value"),
+        };
+        client.add_integration_secret(&secret).await.unwrap();
+        let fetched = client
+            .get_integration_secret(&secret_key(&secret))
+            .await
+            .unwrap();
+        assert_eq!(fetched.unwrap().secret, "value");
+
+        client
+            .delete_integration(&integration_key(&integration))
+            .await
+            .unwrap();
+        assert!(
+            client
+                .get_integration_secret(&secret_key(&secret))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(mock.snapshot().integrations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active() {
+        let workspace = |uuid: u128, mode: WorkspaceMode| WorkspaceFixture {
+            uuid: uuid::Uuid::from_u128(uuid),
+            url: uuid.to_string(),
+            data_id: None,
+            endpoint: Url::parse("ws://localhost:3333/").unwrap(),
+            role: AccountRole::OWNER,
+            status: Some(WorkspaceStatus {
+                mode: Some(mode),
+                ..active_status()
+            }),
+        };
+
+        let mut failing = workspace(4, WorkspaceMode::Upgrading);
+        if let Some(status) = &mut failing.status {
+            status.processing_attempts = Some(DEFAULT_MAX_PROCESSING_ATTEMPTS);
+        }
+
+        let fixture = AccountFixture {
+            workspaces: vec![
+                workspace(1, WorkspaceMode::Active),
+                workspace(2, WorkspaceMode::Creating),
+                workspace(3, WorkspaceMode::Deleted),
+                failing,
+            ],
+            ..Default::default()
+        };
+
+        let mock = MockAccount::start(fixture).await.unwrap();
+        let client = ServiceFactory::new(mock.config())
+            .new_account_client(&Claims::default())
+            .unwrap();
+        let wait = std::time::Duration::from_millis(200);
+
+        let status = client
+            .wait_until_active(uuid::Uuid::from_u128(1), wait, None)
+            .await
+            .unwrap();
+        assert_eq!(status.mode, Some(WorkspaceMode::Active));
+
+        assert!(matches!(
+            client.wait_until_active(uuid::Uuid::from_u128(2), wait, None).await,
+            Err(Error::WorkspaceWaitTimeout(uuid)) if uuid == uuid::Uuid::from_u128(2)
+        ));
+
+        assert!(matches!(
+            client
+                .wait_until_active(uuid::Uuid::from_u128(3), wait, None)
+                .await,
+            Err(Error::WorkspaceFailed { .. })
+        ));
+
+        assert!(matches!(
+            client
+                .wait_until_active(
+                    uuid::Uuid::from_u128(4),
+                    wait,
+                    Some(DEFAULT_MAX_PROCESSING_ATTEMPTS)
+                )
+                .await,
+            Err(Error::WorkspaceFailed { .. })
+        ));
+        assert!(matches!(
+            client
+                .wait_until_active(uuid::Uuid::from_u128(4), wait, None)
+                .await,
+            Err(Error::WorkspaceWaitTimeout(_))
+        ));
+    }
+}
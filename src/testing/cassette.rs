@@ -0,0 +1,585 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use futures::{FutureExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use url::Url;
+
+use super::error_status;
+use crate::services::core::WorkspaceUuid;
+use crate::services::core::classes::OperationDomain;
+use crate::services::core::storage::DomainResult;
+use crate::services::transactor::backend::{Backend, SubscriptionBackend};
+use crate::services::transactor::methods::Method;
+use crate::services::{Status, TokenProvider};
+use crate::{Error, Result};
+
+const SCRUBBED: &str = "[scrubbed]";
+
+/// Fields replaced with a placeholder wherever they appear in params and responses
+const SECRET_FIELDS: &[&str] = &["token", "password", "secret"];
+
+/// Fields of a transaction which differ between runs
+const VOLATILE_TX_FIELDS: &[&str] = &["_id", "modifiedOn", "createdOn"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Ok(Value),
+    Err(Status),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Interaction {
+    Request {
+        /// Method as named in the websocket protocol, e.g. `findAll`
+        method: String,
+        params: Vec<Value>,
+        outcome: Outcome,
+    },
+    /// Transaction broadcast by the transactor
+    Broadcast { tx: Value },
+}
+
+/// Traffic of a [`TransactorClient`](crate::services::transactor::TransactorClient) session
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Cassette {
+    pub workspace: WorkspaceUuid,
+    pub base: Url,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(std::fs::write(path, serde_json::to_vec_pretty(self)?)?)
+    }
+}
+
+/// Replaces secret fields, and any occurrence of `secrets`, with a placeholder
+fn scrub(value: &mut Value, secrets: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                if SECRET_FIELDS.contains(&key.as_str()) {
+                    *value = Value::String(SCRUBBED.to_owned());
+                } else {
+                    scrub(value, secrets);
+                }
+            }
+        }
+
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub(item, secrets)),
+
+        Value::String(string) => {
+            for secret in secrets.iter().filter(|secret| !secret.is_empty()) {
+                if string.contains(secret.as_str()) {
+                    *string = string.replace(secret.as_str(), SCRUBBED);
+                }
+            }
+        }
+
+        _ => {}
+    }
+}
+
+fn remove_fields(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, _| !fields.contains(key));
+            object
+                .values_mut()
+                .for_each(|value| remove_fields(value, fields));
+        }
+
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| remove_fields(item, fields)),
+
+        _ => {}
+    }
+}
+
+struct RecordingInner<B> {
+    backend: B,
+    interactions: Mutex<Vec<Interaction>>,
+    /// Started by the first subscription
+    broadcasts: Mutex<Option<BroadcastStream<Value>>>,
+}
+
+/// Wraps a [`Backend`] and records its requests, responses and broadcast transactions.
+///
+/// Broadcasts are recorded once something subscribes, and are attributed to the request that
+/// completes after them.
+///
+/// The backend's token, and fields such as `token` or `password`, are scrubbed from the cassette.
+pub struct RecordingBackend<B> {
+    inner: Arc<RecordingInner<B>>,
+}
+
+impl<B> Clone for RecordingBackend<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<B: Backend> RecordingBackend<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            inner: Arc::new(RecordingInner {
+                backend,
+                interactions: Mutex::default(),
+                broadcasts: Mutex::default(),
+            }),
+        }
+    }
+
+    pub fn cassette(&self) -> Cassette {
+        self.record_broadcasts();
+
+        Cassette {
+            workspace: self.inner.backend.workspace(),
+            base: self.inner.backend.base().clone(),
+            interactions: self.inner.interactions.lock().unwrap().clone(),
+        }
+    }
+
+    fn secrets(&self) -> Vec<String> {
+        self.inner
+            .backend
+            .provide_token()
+            .map(ToOwned::to_owned)
+            .into_iter()
+            .collect()
+    }
+
+    fn record(&self, method: &str, mut params: Vec<Value>, result: &Result<Value>) {
+        let secrets = self.secrets();
+
+        params.iter_mut().for_each(|param| scrub(param, &secrets));
+
+        let outcome = match result {
+            Ok(value) => {
+                let mut value = value.clone();
+                scrub(&mut value, &secrets);
+                Outcome::Ok(value)
+            }
+            Err(error) => Outcome::Err(error_status(error)),
+        };
+
+        self.inner
+            .interactions
+            .lock()
+            .unwrap()
+            .push(Interaction::Request {
+                method: method.to_owned(),
+                params,
+                outcome,
+            });
+
+        self.record_broadcasts();
+    }
+
+    /// Records broadcasts received so far, after the last recorded request
+    fn record_broadcasts(&self) {
+        let mut broadcasts = self.inner.broadcasts.lock().unwrap();
+        let Some(broadcasts) = broadcasts.as_mut() else {
+            return;
+        };
+
+        let secrets = self.secrets();
+        let mut interactions = self.inner.interactions.lock().unwrap();
+
+        while let Some(Some(tx)) = broadcasts.next().now_or_never() {
+            if let Ok(mut tx) = tx {
+                scrub(&mut tx, &secrets);
+                interactions.push(Interaction::Broadcast { tx });
+            }
+        }
+    }
+}
+
+impl<B: Backend> TokenProvider for RecordingBackend<B> {
+    fn provide_token(&self) -> Option<&str> {
+        self.inner.backend.provide_token()
+    }
+}
+
+impl<B: Backend> Backend for RecordingBackend<B> {
+    async fn get<T: DeserializeOwned + Send>(
+        &self,
+        method: Method,
+        params: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<T> {
+        let params = params.into_iter().collect::<Vec<_>>();
+        let values = params.iter().map(|(_, value)| value.clone()).collect();
+
+        let result = self.inner.backend.get::<Value>(method, params).await;
+        self.record(method.camel(), values, &result);
+
+        Ok(serde_json::from_value(result?)?)
+    }
+
+    async fn post<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        method: Method,
+        body: &Q,
+    ) -> Result<T> {
+        let body = serde_json::to_value(body)?;
+
+        let result = self.inner.backend.post::<Value, _>(method, &body).await;
+        self.record(method.camel(), vec![body], &result);
+
+        Ok(serde_json::from_value(result?)?)
+    }
+
+    async fn domain_request<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        domain: OperationDomain,
+        operation: &str,
+        params: &Q,
+    ) -> Result<DomainResult<T>> {
+        let params = serde_json::to_value(params)?;
+        let values = vec![
+            Value::String(domain.clone()),
+            Value::String(operation.to_owned()),
+            params.clone(),
+        ];
+
+        let result = self
+            .inner
+            .backend
+            .domain_request::<Value, _>(domain, operation, &params)
+            .await
+            .and_then(|result| Ok(serde_json::to_value(result)?));
+        self.record(Method::Request.camel(), values, &result);
+
+        Ok(serde_json::from_value(result?)?)
+    }
+
+    async fn tx_raw<T: Serialize, R: DeserializeOwned + Send>(&self, tx: T) -> Result<R> {
+        let tx = serde_json::to_value(tx)?;
+
+        let result = self.inner.backend.tx_raw::<_, Value>(&tx).await;
+        self.record(Method::Tx.camel(), vec![tx], &result);
+
+        Ok(serde_json::from_value(result?)?)
+    }
+
+    fn base(&self) -> &Url {
+        self.inner.backend.base()
+    }
+
+    fn workspace(&self) -> WorkspaceUuid {
+        self.inner.backend.workspace()
+    }
+}
+
+impl<B: SubscriptionBackend> SubscriptionBackend for RecordingBackend<B> {
+    fn tx_stream(&self) -> BroadcastStream<Value> {
+        self.inner
+            .broadcasts
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| self.inner.backend.tx_stream());
+
+        self.inner.backend.tx_stream()
+    }
+}
+
+struct ReplayInner {
+    cassette: Cassette,
+    used: Mutex<Vec<bool>>,
+    ignored_fields: Vec<String>,
+    tx_broadcast: broadcast::Sender<Value>,
+}
+
+/// Serves responses from a [`Cassette`] instead of a transactor.
+///
+/// A request is answered by the first unused recorded request with the same method and params,
+/// so identical requests are answered in recorded order. Transaction ids and timestamps are not
+/// compared. Broadcasts recorded after a request are sent to subscribers once it is replayed.
+#[derive(Clone)]
+pub struct ReplayBackend {
+    inner: Arc<ReplayInner>,
+}
+
+impl ReplayBackend {
+    pub fn new(cassette: Cassette) -> Self {
+        Self::with_ignored_fields(cassette, Vec::<String>::new())
+    }
+
+    /// Also ignores `fields` at any depth of the params when matching, e.g. generated ids
+    pub fn with_ignored_fields(
+        cassette: Cassette,
+        fields: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+
+        Self {
+            inner: Arc::new(ReplayInner {
+                cassette,
+                used: Mutex::new(used),
+                ignored_fields: fields.into_iter().map(Into::into).collect(),
+                tx_broadcast: broadcast::channel(128).0,
+            }),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Recorded requests which have not been replayed
+    pub fn unused(&self) -> Vec<Interaction> {
+        let used = self.inner.used.lock().unwrap();
+
+        self.inner
+            .cassette
+            .interactions
+            .iter()
+            .zip(used.iter())
+            .filter(|(interaction, used)| {
+                matches!(interaction, Interaction::Request { .. }) && !**used
+            })
+            .map(|(interaction, _)| interaction.clone())
+            .collect()
+    }
+
+    fn normalize(&self, method: &str, params: &[Value]) -> Vec<Value> {
+        let mut params = params.to_vec();
+
+        for param in params.iter_mut() {
+            scrub(param, &[]);
+            remove_fields(param, &self.inner.ignored_fields);
+        }
+
+        if method == Method::Tx.camel()
+            && let Some(Value::Object(tx)) = params.first_mut()
+        {
+            tx.retain(|key, _| !VOLATILE_TX_FIELDS.contains(&key.as_str()));
+        }
+
+        params
+    }
+
+    fn replay(&self, method: &str, params: Vec<Value>) -> Result<Value> {
+        let interactions = &self.inner.cassette.interactions;
+        let mut used = self.inner.used.lock().unwrap();
+        let params = self.normalize(method, &params);
+
+        let mut candidate = None;
+
+        let found = interactions
+            .iter()
+            .enumerate()
+            .find(|(index, interaction)| {
+                let Interaction::Request {
+                    method: recorded_method,
+                    params: recorded_params,
+                    ..
+                } = interaction
+                else {
+                    return false;
+                };
+
+                if used[*index] || recorded_method != method {
+                    return false;
+                }
+
+                let recorded_params = self.normalize(method, recorded_params);
+                if recorded_params == params {
+                    return true;
+                }
+
+                candidate.get_or_insert(recorded_params);
+                false
+            });
+
+        let Some((index, Interaction::Request { outcome, .. })) = found else {
+            let params = serde_json::to_string(&params).unwrap_or_default();
+
+            let message = match candidate {
+                Some(candidate) => format!(
+                    "no unused `{method}` request with params {params}, the next unused `{method}` was recorded with {}",
+                    serde_json::to_string(&candidate).unwrap_or_default()
+                ),
+                None => {
+                    format!("no unused `{method}` request left, requested with params {params}")
+                }
+            };
+
+            return Err(Error::Cassette(message));
+        };
+
+        used[index] = true;
+
+        for (index, interaction) in interactions.iter().enumerate().skip(index + 1) {
+            let Interaction::Broadcast { tx } = interaction else {
+                break;
+            };
+
+            if !used[index] {
+                used[index] = true;
+                let _ = self.inner.tx_broadcast.send(tx.clone());
+            }
+        }
+
+        match outcome {
+            Outcome::Ok(value) => Ok(value.clone()),
+            Outcome::Err(status) => Err(Error::ServiceError(status.clone())),
+        }
+    }
+}
+
+impl TokenProvider for ReplayBackend {
+    fn provide_token(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Backend for ReplayBackend {
+    async fn get<T: DeserializeOwned + Send>(
+        &self,
+        method: Method,
+        params: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<T> {
+        let params = params.into_iter().map(|(_, value)| value).collect();
+        Ok(serde_json::from_value(
+            self.replay(method.camel(), params)?,
+        )?)
+    }
+
+    async fn post<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        method: Method,
+        body: &Q,
+    ) -> Result<T> {
+        let params = vec![serde_json::to_value(body)?];
+        Ok(serde_json::from_value(
+            self.replay(method.camel(), params)?,
+        )?)
+    }
+
+    async fn domain_request<T: DeserializeOwned + Send, Q: Serialize>(
+        &self,
+        domain: OperationDomain,
+        operation: &str,
+        params: &Q,
+    ) -> Result<DomainResult<T>> {
+        let params = vec![
+            Value::String(domain),
+            Value::String(operation.to_owned()),
+            serde_json::to_value(params)?,
+        ];
+
+        Ok(serde_json::from_value(
+            self.replay(Method::Request.camel(), params)?,
+        )?)
+    }
+
+    async fn tx_raw<T: Serialize, R: DeserializeOwned + Send>(&self, tx: T) -> Result<R> {
+        let params = vec![serde_json::to_value(tx)?];
+        Ok(serde_json::from_value(
+            self.replay(Method::Tx.camel(), params)?,
+        )?)
+    }
+
+    fn base(&self) -> &Url {
+        &self.inner.cassette.base
+    }
+
+    fn workspace(&self) -> WorkspaceUuid {
+        self.inner.cassette.workspace
+    }
+}
+
+impl SubscriptionBackend for ReplayBackend {
+    fn tx_stream(&self) -> BroadcastStream<Value> {
+        self.inner.tx_broadcast.subscribe().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::event::Class;
+    use crate::services::transactor::TransactorClient;
+    use crate::services::transactor::document::{DocumentClient, FindOptions};
+    use crate::testing::MemoryBackend;
+    use crate::testing::test_support::{Issue, issue};
+
+    async fn session<B: SubscriptionBackend>(client: &TransactorClient<B>) -> Result<Vec<Issue>> {
+        let mut events = client.subscribe::<Issue>().await;
+        client
+            .tx::<_, Value>(issue(
+                "i1",
+                json!({ "title": "First", "password": "hunter2" }),
+            ))
+            .await?;
+        events.next().await.expect("broadcast")?;
+
+        let found = client
+            .find_all(
+                Issue::CLASS,
+                json!({ "title": "First" }),
+                &FindOptions::default(),
+            )
+            .await?;
+
+        Ok(found.value)
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let client =
+            TransactorClient::new_memory(MemoryBackend::new(uuid::Uuid::nil())).into_recording();
+        assert_eq!(session(&client).await.unwrap().len(), 1);
+
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", rand::random::<u64>()));
+        client.recorder().cassette().save(&path).unwrap();
+        let replay = ReplayBackend::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let recorded = serde_json::to_string(&replay.inner.cassette).unwrap();
+        assert!(!recorded.contains("hunter2"));
+
+        let client = TransactorClient::new_replay(replay.clone());
+        let found = session(&client).await.unwrap();
+        assert_eq!(found[0].title, "First");
+        assert!(replay.unused().is_empty());
+
+        let error = client
+            .find_all::<_, Value>(Issue::CLASS, json!({}), &FindOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Cassette(message) if message.contains("findAll")));
+    }
+}
@@ -0,0 +1,176 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use serde::Deserialize;
+use url::Url;
+
+use super::{Server, TOKEN_SECRET, bearer_claims};
+use crate::{Config, ConfigBuilder, Result};
+
+/// Data [`MockKvs`] is seeded with, values by key by namespace
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct KvsFixture {
+    pub namespaces: HashMap<String, HashMap<String, String>>,
+}
+
+impl KvsFixture {
+    /// Reads a fixture from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+}
+
+type Store = HashMap<(String, String), Vec<u8>>;
+
+/// Key-value service on a random localhost port.
+///
+/// Requests must carry a token signed with [`TOKEN_SECRET`].
+pub struct MockKvs {
+    server: Server,
+    state: Arc<Mutex<Store>>,
+}
+
+impl MockKvs {
+    pub async fn start(fixture: KvsFixture) -> Result<Self> {
+        let store = fixture
+            .namespaces
+            .into_iter()
+            .flat_map(|(namespace, values)| {
+                values
+                    .into_iter()
+                    .map(move |(key, value)| ((namespace.clone(), key), value.into_bytes()))
+            })
+            .collect::<Store>();
+
+        let state = Arc::new(Mutex::new(store));
+
+        let router = Router::new()
+            .route(
+                "/api/{namespace}/{*key}",
+                get(get_value).post(upsert_value).delete(delete_value),
+            )
+            .with_state(state.clone());
+
+        Ok(Self {
+            server: Server::start(router).await?,
+            state,
+        })
+    }
+
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/", self.server.addr)).expect("valid url")
+    }
+
+    /// Config pointing `kvs_service` at this server
+    pub fn config(&self) -> Config {
+        ConfigBuilder::default()
+            .token_secret(TOKEN_SECRET)
+            .kvs_service(self.url())
+            .build()
+            .expect("complete config")
+    }
+
+    pub fn get(&self, namespace: &str, key: &str) -> Option<Vec<u8>> {
+        self.state
+            .lock()
+            .unwrap()
+            .get(&(namespace.to_owned(), key.to_owned()))
+            .cloned()
+    }
+}
+
+async fn get_value(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath(key): UrlPath<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    match state.lock().unwrap().get(&key) {
+        Some(value) => value.clone().into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn upsert_value(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath(key): UrlPath<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    state.lock().unwrap().insert(key, body.to_vec());
+    StatusCode::NO_CONTENT
+}
+
+async fn delete_value(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath(key): UrlPath<(String, String)>,
+    headers: HeaderMap,
+) -> StatusCode {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    state.lock().unwrap().remove(&key);
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceFactory;
+    use crate::services::jwt::Claims;
+
+    #[tokio::test]
+    async fn test_mock_kvs() {
+        let fixture = KvsFixture {
+            namespaces: HashMap::from([(
+                String::from("ns"),
+                HashMap::from([(String::from("seeded"), String::from("value"))]),
+            )]),
+        };
+
+        let mock = MockKvs::start(fixture).await.unwrap();
+        let client = ServiceFactory::new(mock.config())
+            .new_kvs_client("ns", &Claims::default())
+            .unwrap();
+
+        assert_eq!(client.get("seeded").await.unwrap(), Some(b"value".to_vec()));
+        assert_eq!(client.get("missing").await.unwrap(), None);
+
+        client.upsert("nested/key", b"bytes").await.unwrap();
+        assert_eq!(mock.get("ns", "nested/key"), Some(b"bytes".to_vec()));
+
+        client.delete("nested/key").await.unwrap();
+        assert_eq!(client.get("nested/key").await.unwrap(), None);
+    }
+}
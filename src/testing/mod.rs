@@ -0,0 +1,147 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Fakes of the platform services for tests, enabled by the `testing` feature
+
+mod account;
+mod cassette;
+mod kvs;
+mod pulse;
+mod transactor;
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+
+use axum::Router;
+use axum::http::{HeaderMap, header};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::services::jwt::Claims;
+use crate::services::{Severity, Status};
+use crate::{Error, Result};
+
+pub use crate::services::transactor::backend::memory::MemoryBackend;
+pub use account::{AccountFixture, MockAccount, WorkspaceFixture};
+pub use cassette::{Cassette, Interaction, Outcome, RecordingBackend, ReplayBackend};
+pub use kvs::{KvsFixture, MockKvs};
+pub use pulse::{MockPulse, PulseFixture, PulseObject};
+pub use transactor::{Fault, MockTransactor, RecordedRequest, Transport};
+
+/// Secret the stand-in services sign and verify tokens with
+pub const TOKEN_SECRET: &str = "secret";
+
+/// Axum server on a random localhost port, shut down on drop
+struct Server {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Server {
+    async fn start(router: Router) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            addr,
+            shutdown: Some(shutdown),
+        })
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Claims of the bearer token, if it is signed with [`TOKEN_SECRET`]
+fn bearer_claims(headers: &HeaderMap) -> Option<Claims> {
+    let token = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+
+    Claims::from_token(token, TOKEN_SECRET).ok()
+}
+
+fn status(code: &str) -> Status {
+    Status {
+        severity: Severity::Error,
+        code: code.to_owned(),
+        params: HashMap::new(),
+    }
+}
+
+/// Status a service would reply with for `error`
+fn error_status(error: &Error) -> Status {
+    match error {
+        Error::ServiceError(status) => status.clone(),
+        error => Status {
+            severity: Severity::Error,
+            code: String::from("platform:status:UnknownError"),
+            params: HashMap::from([(String::from("message"), error.to_string().into())]),
+        },
+    }
+}
+
+/// Document type shared by the tests of the stand-in backends
+#[cfg(test)]
+pub(crate) mod test_support {
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    use crate::services::event::{Class, Event};
+    use crate::services::transactor::document::CreateDocument;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Issue {
+        #[serde(rename = "_id", default)]
+        pub id: String,
+        pub title: String,
+        #[serde(default)]
+        pub estimation: i64,
+    }
+
+    impl Class for Issue {
+        const CLASS: &'static str = "tracker:class:Issue";
+    }
+
+    impl Event for Issue {}
+
+    /// Creates an [`Issue`] with the given attributes in `space`
+    pub fn issue(id: &str, attributes: Value) -> CreateDocument<Value> {
+        CreateDocument::builder()
+            .object_id(id)
+            .object_class(Issue::CLASS)
+            .object_space("space")
+            .attributes(attributes)
+            .build()
+            .unwrap()
+    }
+}
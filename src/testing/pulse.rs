@@ -0,0 +1,377 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Json, Path as UrlPath, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Router, body::Bytes};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use super::{Server, TOKEN_SECRET, bearer_claims};
+use crate::services::core::WorkspaceUuid;
+use crate::{Config, ConfigBuilder, Result};
+
+/// Object [`MockPulse`] is seeded with
+#[derive(Deserialize, Debug, Clone)]
+pub struct PulseObject {
+    pub workspace: WorkspaceUuid,
+    pub key: String,
+    pub data: String,
+
+    /// Seconds until the object expires, never when absent
+    #[serde(default)]
+    pub ttl: Option<u64>,
+}
+
+/// Data [`MockPulse`] is seeded with
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PulseFixture {
+    pub objects: Vec<PulseObject>,
+}
+
+impl PulseFixture {
+    /// Reads a fixture from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+}
+
+struct Entry {
+    data: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl Entry {
+    /// Opaque tag of the data, for `If-Match`
+    fn etag(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.data.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    fn to_json(&self, key: &str, now: DateTime<Utc>) -> serde_json::Value {
+        let ttl = self
+            .expires_at
+            .map(|expires_at| (expires_at - now).num_seconds().max(0))
+            .unwrap_or_default();
+
+        json!({
+            "key": key,
+            "data": self.data,
+            "expires_at": ttl,
+            "etag": self.etag(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Store(HashMap<WorkspaceUuid, BTreeMap<String, Entry>>);
+
+impl Store {
+    /// Objects of a workspace, with expired ones evicted
+    fn workspace(&mut self, workspace: WorkspaceUuid) -> &mut BTreeMap<String, Entry> {
+        let now = Utc::now();
+        let objects = self.0.entry(workspace).or_default();
+        objects.retain(|_, entry| !entry.is_expired(now));
+        objects
+    }
+}
+
+/// Pulse service on a random localhost port, with lazy TTL expiry and `If-Match` /
+/// `If-None-Match` preconditions.
+///
+/// Requests must carry a token signed with [`TOKEN_SECRET`].
+pub struct MockPulse {
+    server: Server,
+    state: Arc<Mutex<Store>>,
+}
+
+impl MockPulse {
+    pub async fn start(fixture: PulseFixture) -> Result<Self> {
+        let mut store = Store::default();
+        let now = Utc::now();
+
+        for object in fixture.objects {
+            store.workspace(object.workspace).insert(
+                object.key,
+                Entry {
+                    data: object.data,
+                    expires_at: object.ttl.map(|ttl| now + TimeDelta::seconds(ttl as i64)),
+                },
+            );
+        }
+
+        let state = Arc::new(Mutex::new(store));
+
+        let router = Router::new()
+            .route("/api/{workspace}/", get(list_root))
+            .route(
+                "/api/{workspace}/{*key}",
+                get(get_object).put(put_object).delete(delete_object),
+            )
+            .with_state(state.clone());
+
+        Ok(Self {
+            server: Server::start(router).await?,
+            state,
+        })
+    }
+
+    /// REST endpoint, ending in `/api/`
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/api/", self.server.addr)).expect("valid url")
+    }
+
+    /// Config pointing `pulse_service` at this server
+    pub fn config(&self) -> Config {
+        ConfigBuilder::default()
+            .token_secret(TOKEN_SECRET)
+            .pulse_service(self.url())
+            .build()
+            .expect("complete config")
+    }
+
+    pub fn get(&self, workspace: WorkspaceUuid, key: &str) -> Option<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .workspace(workspace)
+            .get(key)
+            .map(|entry| entry.data.clone())
+    }
+}
+
+/// Checks `If-Match` and `If-None-Match` against the current object
+fn precondition(headers: &HeaderMap, current: Option<&Entry>) -> bool {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(tag) = header(header::IF_NONE_MATCH) {
+        match current {
+            Some(entry) if tag == "*" || tag == entry.etag() => return false,
+            _ => {}
+        }
+    }
+
+    if let Some(tag) = header(header::IF_MATCH) {
+        match current {
+            Some(entry) if tag == "*" || tag == entry.etag() => {}
+            _ => return false,
+        }
+    }
+
+    true
+}
+
+fn list(store: &mut Store, workspace: WorkspaceUuid, prefix: &str) -> Response {
+    let now = Utc::now();
+
+    let objects = store
+        .workspace(workspace)
+        .iter()
+        .filter(|(key, _)| key.starts_with(prefix))
+        .map(|(key, entry)| entry.to_json(key, now))
+        .collect::<Vec<_>>();
+
+    Json(objects).into_response()
+}
+
+async fn list_root(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath(workspace): UrlPath<WorkspaceUuid>,
+    headers: HeaderMap,
+) -> Response {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    list(&mut state.lock().unwrap(), workspace, "")
+}
+
+async fn get_object(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath((workspace, key)): UrlPath<(WorkspaceUuid, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let mut store = state.lock().unwrap();
+
+    if key.ends_with('/') {
+        return list(&mut store, workspace, &key);
+    }
+
+    match store.workspace(workspace).get(&key) {
+        Some(entry) => Json(entry.to_json(&key, Utc::now())).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn put_object(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath((workspace, key)): UrlPath<(WorkspaceUuid, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let Ok(data) = String::from_utf8(body.to_vec()) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    let number = |name: &str| {
+        headers.get(name).map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+        })
+    };
+
+    let expires_at = match (number("huly-ttl"), number("huly-expire-at")) {
+        (Some(Some(ttl)), None) => Some(Utc::now() + TimeDelta::seconds(ttl)),
+        (None, Some(Some(at))) => match DateTime::from_timestamp(at, 0) {
+            Some(at) => Some(at),
+            None => return StatusCode::BAD_REQUEST,
+        },
+        (None, None) => None,
+        _ => return StatusCode::BAD_REQUEST,
+    };
+
+    let mut store = state.lock().unwrap();
+    let objects = store.workspace(workspace);
+
+    if !precondition(&headers, objects.get(&key)) {
+        return StatusCode::PRECONDITION_FAILED;
+    }
+
+    objects.insert(key, Entry { data, expires_at });
+    StatusCode::NO_CONTENT
+}
+
+async fn delete_object(
+    State(state): State<Arc<Mutex<Store>>>,
+    UrlPath((workspace, key)): UrlPath<(WorkspaceUuid, String)>,
+    headers: HeaderMap,
+) -> StatusCode {
+    if bearer_claims(&headers).is_none() {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let mut store = state.lock().unwrap();
+    let objects = store.workspace(workspace);
+
+    if !precondition(&headers, objects.get(&key)) {
+        return StatusCode::PRECONDITION_FAILED;
+    }
+
+    match objects.remove(&key) {
+        Some(_) => StatusCode::NO_CONTENT,
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceFactory;
+    use crate::services::jwt::Claims;
+    use crate::services::pulse::{Expiration, PutMode};
+
+    #[tokio::test]
+    async fn test_mock_pulse() {
+        let workspace = uuid::Uuid::from_u128(1);
+
+        let fixture = PulseFixture {
+            objects: vec![PulseObject {
+                workspace,
+                key: String::from("presence/a"),
+                data: String::from("online"),
+                ttl: Some(60),
+            }],
+        };
+
+        let mock = MockPulse::start(fixture).await.unwrap();
+        let token = Claims::default().encode(&TOKEN_SECRET.into()).unwrap();
+        let client = ServiceFactory::new(mock.config())
+            .new_pulse_client(workspace, token)
+            .unwrap();
+
+        let object = client.get("presence/a").await.unwrap().unwrap();
+        assert_eq!(object.data, "online");
+        assert!(matches!(object.expires_at, Expiration::InSeconds(ttl) if ttl > 0 && ttl <= 60));
+
+        assert!(
+            client
+                .put("presence/a", "away".into(), None, PutMode::Insert)
+                .await
+                .is_err()
+        );
+        assert!(
+            client
+                .put(
+                    "presence/a",
+                    "away".into(),
+                    None,
+                    PutMode::Equal("stale".into())
+                )
+                .await
+                .is_err()
+        );
+        client
+            .put(
+                "presence/a",
+                "away".into(),
+                None,
+                PutMode::Equal(object.etag),
+            )
+            .await
+            .unwrap();
+        client
+            .put(
+                "presence/b",
+                "online".into(),
+                Some(Expiration::InSeconds(0)),
+                PutMode::Insert,
+            )
+            .await
+            .unwrap();
+
+        let listed = client.list("presence").await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].data, "away");
+
+        client.delete("presence/a", PutMode::Update).await.unwrap();
+        assert!(client.delete("presence/a", PutMode::Upsert).await.is_err());
+        assert_eq!(mock.get(workspace, "presence/a"), None);
+    }
+}
@@ -0,0 +1,901 @@
+//
+// Copyright © 2025 Hardcore Engineering Inc.
+//
+// Licensed under the Eclipse Public License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License. You may
+// obtain a copy of the License at https://www.eclipse.org/legal/epl-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get};
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::{broadcast, mpsc};
+use url::Url;
+
+use super::{Server, error_status};
+use crate::services::core::{Account, AccountRole};
+use crate::services::rpc::RateLimitInfo;
+use crate::services::transactor::backend::memory::MemoryBackend;
+use crate::services::transactor::backend::{Backend, SubscriptionBackend};
+use crate::services::transactor::methods::Method;
+use crate::services::{Severity, Status};
+use crate::{Error, Result};
+
+const PONG: &str = "pong!";
+
+/// Misbehaviour injected into the reply to a single request
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Waits before replying normally
+    Delay(Duration),
+    /// Replies with an error, over HTTP with status `500`
+    Error(Status),
+    /// Splits array results into websocket chunks of this many items, HTTP bodies into chunks of this many bytes
+    Chunked(usize),
+    /// Rejects the request as rate limited, over HTTP with status `429`
+    RateLimited { retry_after: Duration },
+    /// Drops the connection without replying
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Http,
+    Ws,
+}
+
+/// A request received by [`MockTransactor`]
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub transport: Transport,
+    /// Method as named in the websocket protocol, e.g. `findAll`
+    pub method: String,
+    pub params: Vec<Value>,
+    pub token: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Control {
+    Ping,
+    Disconnect,
+}
+
+struct MockState {
+    backend: MemoryBackend,
+    account: Account,
+    faults: Mutex<HashMap<String, VecDeque<Fault>>>,
+    responses: Mutex<HashMap<(String, String), VecDeque<Value>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+    control: broadcast::Sender<Control>,
+}
+
+impl MockState {
+    fn record(
+        &self,
+        transport: Transport,
+        method: &str,
+        params: Vec<Value>,
+        token: Option<String>,
+    ) {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            transport,
+            method: method.to_owned(),
+            params,
+            token,
+        });
+    }
+
+    fn take_fault(&self, method: &str) -> Option<Fault> {
+        self.faults
+            .lock()
+            .unwrap()
+            .get_mut(method)
+            .and_then(VecDeque::pop_front)
+    }
+
+    async fn call(&self, method: Method, params: Vec<Value>) -> Result<Value> {
+        match method {
+            Method::Account => Ok(serde_json::to_value(&self.account)?),
+
+            Method::Tx => {
+                let tx = params.into_iter().next().unwrap_or_default();
+                self.backend.tx_raw(tx).await
+            }
+
+            Method::FindAll => {
+                let params = ["class", "query", "options"]
+                    .into_iter()
+                    .map(String::from)
+                    .zip(params);
+
+                self.backend.get(method, params).await
+            }
+
+            Method::Request => {
+                let domain = params.first().and_then(Value::as_str).unwrap_or_default();
+                let operation = params
+                    .get(1)
+                    .and_then(Value::as_object)
+                    .and_then(|operations| operations.keys().next())
+                    .cloned()
+                    .unwrap_or_default();
+
+                let value = self
+                    .responses
+                    .lock()
+                    .unwrap()
+                    .get_mut(&(domain.to_owned(), operation))
+                    .and_then(VecDeque::pop_front)
+                    .ok_or(Error::Other("UnscriptedDomainRequest"))?;
+
+                Ok(json!({ "domain": domain, "value": value }))
+            }
+
+            _ => Err(Error::Other("UnsupportedMethod")),
+        }
+    }
+}
+
+/// Transactor speaking the REST and websocket protocols on a random localhost port.
+///
+/// Data is served from a [`MemoryBackend`], and transactions applied to it are broadcast to
+/// connected websockets. Faults scripted with [`MockTransactor::fault`] alter the replies, domain
+/// requests are answered with the values scripted with [`MockTransactor::respond`].
+pub struct MockTransactor {
+    server: Server,
+    state: Arc<MockState>,
+}
+
+impl MockTransactor {
+    pub async fn start(backend: MemoryBackend) -> Result<Self> {
+        let account = match backend.get::<Account>(Method::Account, []).await {
+            Ok(account) => account,
+            Err(_) => default_account(),
+        };
+
+        let state = Arc::new(MockState {
+            backend,
+            account,
+            faults: Mutex::default(),
+            responses: Mutex::default(),
+            requests: Mutex::default(),
+            control: broadcast::channel(16).0,
+        });
+
+        let router = Router::new()
+            .route(
+                "/api/v1/{method}/{workspace}",
+                get(http_get).post(http_post),
+            )
+            .route(
+                "/api/v1/event/{domain}/{operation}/{workspace}",
+                get(http_domain_request),
+            )
+            .route("/{token}", any(ws_upgrade))
+            .with_state(state.clone());
+
+        Ok(Self {
+            server: Server::start(router).await?,
+            state,
+        })
+    }
+
+    /// Base url to pass to [`TransactorClient::new`](crate::services::transactor::TransactorClient::new)
+    /// or [`TransactorClient::new_ws`](crate::services::transactor::TransactorClient::new_ws)
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("ws://{}/", self.server.addr)).expect("valid url")
+    }
+
+    pub fn backend(&self) -> &MemoryBackend {
+        &self.state.backend
+    }
+
+    /// Applies `fault` to the next request of `method`, faults for the same method are applied in order
+    pub fn fault(&self, method: Method, fault: Fault) {
+        self.state
+            .faults
+            .lock()
+            .unwrap()
+            .entry(method.camel().to_owned())
+            .or_default()
+            .push_back(fault);
+    }
+
+    /// Replies to the next domain request of `operation` in `domain` with `value`, replies for the
+    /// same operation are consumed in order and unscripted requests fail
+    pub fn respond(&self, domain: &str, operation: &str, value: Value) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .entry((domain.to_owned(), operation.to_owned()))
+            .or_default()
+            .push_back(value);
+    }
+
+    /// Requests received so far, pings and HELLOs included
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Sends a ping to every connected websocket, their pongs are recorded as `pong` requests
+    pub fn ping(&self) {
+        let _ = self.state.control.send(Control::Ping);
+    }
+
+    /// Closes every connected websocket
+    pub fn disconnect(&self) {
+        let _ = self.state.control.send(Control::Disconnect);
+    }
+}
+
+impl Drop for MockTransactor {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+fn default_account() -> Account {
+    Account {
+        uuid: uuid::Uuid::nil(),
+        role: AccountRole::USER,
+        primary_social_id: String::from("mock"),
+        social_ids: vec![String::from("mock")],
+        full_social_ids: Vec::new(),
+    }
+}
+
+fn rate_limit(retry_after: Duration) -> (Status, RateLimitInfo) {
+    let status = Status {
+        severity: Severity::Error,
+        code: String::from("platform:status:RateLimitExceeded"),
+        params: HashMap::new(),
+    };
+
+    let info = RateLimitInfo {
+        remaining: 0,
+        limit: 1,
+        current: 1,
+        reset: (Utc::now().timestamp_millis() + retry_after.as_millis() as i64) as f64,
+        retry_after: Some(retry_after.as_millis() as u32),
+    };
+
+    (status, info)
+}
+
+/// Splits an array result, or the `value` array of a find result, into chunks of `size` items
+fn chunks(result: Value, size: usize) -> Vec<Value> {
+    let size = size.max(1);
+
+    match result {
+        Value::Array(items) if !items.is_empty() => items
+            .chunks(size)
+            .map(|chunk| Value::Array(chunk.to_vec()))
+            .collect(),
+
+        Value::Object(object) if object.get("value").is_some_and(Value::is_array) => {
+            let items = object["value"].as_array().unwrap();
+            if items.is_empty() {
+                return vec![Value::Object(object)];
+            }
+
+            items
+                .chunks(size)
+                .map(|chunk| {
+                    let mut object = object.clone();
+                    object.insert(String::from("value"), Value::Array(chunk.to_vec()));
+                    Value::Object(object)
+                })
+                .collect()
+        }
+
+        result => vec![result],
+    }
+}
+
+fn bearer(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(ToOwned::to_owned)
+}
+
+/// Query parameters are JSON encoded, except plain strings
+fn query_param(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()))
+}
+
+async fn http_reply(state: &MockState, method: Method, params: Vec<Value>) -> Response {
+    let fault = state.take_fault(method.camel());
+
+    match fault {
+        Some(Fault::Delay(delay)) => tokio::time::sleep(delay).await,
+
+        Some(Fault::Error(status)) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, axum::Json(status)).into_response();
+        }
+
+        Some(Fault::RateLimited { retry_after }) => {
+            let (status, info) = rate_limit(retry_after);
+            let headers = [
+                (
+                    header::RETRY_AFTER,
+                    retry_after.as_secs().max(1).to_string(),
+                ),
+                (
+                    header::HeaderName::from_static("x-ratelimit-limit"),
+                    info.limit.to_string(),
+                ),
+                (
+                    header::HeaderName::from_static("x-ratelimit-remaining"),
+                    info.remaining.to_string(),
+                ),
+                (
+                    header::HeaderName::from_static("x-ratelimit-reset"),
+                    info.reset.to_string(),
+                ),
+            ];
+
+            return (StatusCode::TOO_MANY_REQUESTS, headers, axum::Json(status)).into_response();
+        }
+
+        Some(Fault::Disconnect) => {
+            let body = futures::stream::once(async {
+                Err::<Bytes, _>(std::io::Error::other("disconnected by MockTransactor"))
+            });
+
+            return Body::from_stream(body).into_response();
+        }
+
+        Some(Fault::Chunked(_)) | None => {}
+    }
+
+    let result = match state.call(method, params).await {
+        Ok(result) => result,
+        Err(error) => {
+            return (StatusCode::BAD_REQUEST, axum::Json(error_status(&error))).into_response();
+        }
+    };
+
+    match fault {
+        Some(Fault::Chunked(size)) => {
+            let body = serde_json::to_vec(&result).unwrap_or_default();
+            let chunks = body
+                .chunks(size.max(1))
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>();
+
+            (
+                [(header::CONTENT_TYPE, "application/json")],
+                Body::from_stream(futures::stream::iter(chunks)),
+            )
+                .into_response()
+        }
+
+        _ => axum::Json(result).into_response(),
+    }
+}
+
+async fn http_get(
+    State(state): State<Arc<MockState>>,
+    Path((method, _workspace)): Path<(String, String)>,
+    Query(query): Query<Vec<(String, String)>>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(method) = Method::from_kebab(&method) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let params = query
+        .iter()
+        .map(|(_, value)| query_param(value))
+        .collect::<Vec<_>>();
+
+    state.record(
+        Transport::Http,
+        method.camel(),
+        params.clone(),
+        bearer(&headers),
+    );
+    http_reply(&state, method, params).await
+}
+
+async fn http_post(
+    State(state): State<Arc<MockState>>,
+    Path((method, _workspace)): Path<(String, String)>,
+    headers: HeaderMap,
+    axum::Json(body): axum::Json<Value>,
+) -> Response {
+    let Some(method) = Method::from_kebab(&method) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    state.record(
+        Transport::Http,
+        method.camel(),
+        vec![body.clone()],
+        bearer(&headers),
+    );
+    http_reply(&state, method, vec![body]).await
+}
+
+async fn http_domain_request(
+    State(state): State<Arc<MockState>>,
+    Path((domain, operation, _workspace)): Path<(String, String, String)>,
+    Query(query): Query<Vec<(String, String)>>,
+    headers: HeaderMap,
+) -> Response {
+    let params = query
+        .iter()
+        .find(|(name, _)| name == "params")
+        .map(|(_, value)| query_param(value))
+        .unwrap_or_default();
+
+    let params = vec![
+        Value::String(domain),
+        json!({ operation: { "params": params } }),
+    ];
+
+    state.record(
+        Transport::Http,
+        Method::Request.camel(),
+        params.clone(),
+        bearer(&headers),
+    );
+    http_reply(&state, Method::Request, params).await
+}
+
+enum Reply {
+    Json(Value),
+    Pong,
+    Disconnect,
+}
+
+async fn ws_upgrade(
+    State(state): State<Arc<MockState>>,
+    Path(token): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| ws_connection(state, token, socket))
+}
+
+async fn ws_connection(state: Arc<MockState>, token: String, socket: WebSocket) {
+    let (mut write, mut read) = socket.split();
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<Reply>();
+    let mut txes = state.backend.tx_stream();
+    let mut control = state.control.subscribe();
+    let mut binary = false;
+
+    let encode = |value: &Value, binary: bool| {
+        if binary {
+            Message::Binary(serde_json::to_vec(value).unwrap_or_default().into())
+        } else {
+            Message::Text(value.to_string().into())
+        }
+    };
+
+    loop {
+        let message = tokio::select! {
+            message = read.next() => {
+                let request = match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if text.as_str() == PONG {
+                            state.record(Transport::Ws, "pong", Vec::new(), Some(token.clone()));
+                            continue;
+                        }
+
+                        serde_json::from_str::<Value>(text.as_str())
+                    }
+                    Some(Ok(Message::Binary(bytes))) => {
+                        if bytes.as_ref() == PONG.as_bytes() {
+                            state.record(Transport::Ws, "pong", Vec::new(), Some(token.clone()));
+                            continue;
+                        }
+
+                        serde_json::from_slice::<Value>(&bytes)
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let Ok(request) = request else {
+                    continue;
+                };
+
+                if request["method"] == Method::Hello.camel() {
+                    binary = request["binary"].as_bool().unwrap_or(false);
+                }
+
+                tokio::spawn(ws_reply(state.clone(), token.clone(), request, binary, reply_tx.clone()));
+                continue;
+            }
+
+            Some(reply) = reply_rx.recv() => match reply {
+                Reply::Json(value) => encode(&value, binary),
+                Reply::Pong if binary => Message::Binary(PONG.into()),
+                Reply::Pong => Message::Text(PONG.into()),
+                Reply::Disconnect => break,
+            },
+
+            Some(tx) = txes.next() => match tx {
+                Ok(tx) => encode(&json!({ "result": [tx] }), binary),
+                Err(_) => continue,
+            },
+
+            control = control.recv() => match control {
+                Ok(Control::Ping) => encode(&json!({ "result": "ping" }), binary),
+                Ok(Control::Disconnect) | Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            },
+        };
+
+        if write.send(message).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = write.close().await;
+}
+
+async fn ws_reply(
+    state: Arc<MockState>,
+    token: String,
+    request: Value,
+    binary: bool,
+    reply_tx: mpsc::UnboundedSender<Reply>,
+) {
+    let id = request["id"].clone();
+    let method = request["method"].as_str().unwrap_or_default().to_owned();
+    let params = request["params"].as_array().cloned().unwrap_or_default();
+
+    state.record(Transport::Ws, &method, params.clone(), Some(token));
+
+    let reply = |mut value: Value| {
+        value["id"] = id.clone();
+        let _ = reply_tx.send(Reply::Json(value));
+    };
+
+    let fault = state.take_fault(&method);
+
+    match &fault {
+        Some(Fault::Delay(delay)) => tokio::time::sleep(*delay).await,
+
+        Some(Fault::Error(status)) => return reply(json!({ "error": status })),
+
+        Some(Fault::RateLimited { retry_after }) => {
+            let (status, info) = rate_limit(*retry_after);
+            return reply(json!({ "error": status, "rateLimit": info }));
+        }
+
+        Some(Fault::Disconnect) => {
+            let _ = reply_tx.send(Reply::Disconnect);
+            return;
+        }
+
+        Some(Fault::Chunked(_)) | None => {}
+    }
+
+    match Method::from_camel(&method) {
+        Some(Method::Ping) => {
+            let _ = reply_tx.send(Reply::Pong);
+        }
+
+        Some(Method::Hello) => reply(json!({
+            "result": "hello",
+            "binary": binary,
+            "serverVersion": "mock",
+            "account": state.account,
+            "useCompression": false,
+        })),
+
+        Some(method) => match state.call(method, params).await {
+            Ok(result) => match fault {
+                Some(Fault::Chunked(size)) => {
+                    let chunks = chunks(result, size);
+                    let count = chunks.len();
+
+                    for (index, chunk) in chunks.into_iter().enumerate() {
+                        reply(json!({
+                            "result": chunk,
+                            "chunk": { "index": index, "final": index + 1 == count },
+                        }));
+                    }
+                }
+
+                _ => reply(json!({ "result": result })),
+            },
+
+            Err(error) => reply(json!({ "error": error_status(&error) })),
+        },
+
+        None => reply(json!({ "error": error_status(&Error::Other("UnknownMethod")) })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::core::FindResult;
+    use crate::services::core::tx::WorkspaceEvent;
+    use crate::services::event::Class;
+    use crate::services::transactor::TransactorClient;
+    use crate::services::transactor::backend::ws::{UpgradePolicy, WsBackendOpts};
+    use crate::services::transactor::document::{DocumentClient, FindOptions};
+    use crate::services::transactor::subscription::TxEvent;
+    use crate::testing::test_support::{Issue, issue};
+
+    fn upgrade_scheduled() -> Value {
+        json!({
+            "_id": "event",
+            "_class": "core:class:TxWorkspaceEvent",
+            "space": "core:space:Tx",
+            "objectSpace": "core:space:Tx",
+            "modifiedBy": "core:account:System",
+            "modifiedOn": 0,
+            "event": 0,
+            "params": {},
+        })
+    }
+
+    async fn find_issues<B: Backend>(client: &TransactorClient<B>) -> Result<FindResult<Value>> {
+        client
+            .find_all(Issue::CLASS, json!({}), &FindOptions::default())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_http_faults() {
+        let mock = MockTransactor::start(MemoryBackend::new(uuid::Uuid::nil()))
+            .await
+            .unwrap();
+
+        let http = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+        let client = TransactorClient::new(http, mock.url(), uuid::Uuid::nil(), "token").unwrap();
+
+        client
+            .tx::<_, Value>(issue("i1", json!({ "title": "i1" })))
+            .await
+            .unwrap();
+
+        mock.fault(Method::FindAll, Fault::Chunked(3));
+        assert_eq!(find_issues(&client).await.unwrap().value.len(), 1);
+
+        mock.fault(
+            Method::FindAll,
+            Fault::RateLimited {
+                retry_after: Duration::from_secs(1),
+            },
+        );
+        assert!(matches!(
+            find_issues(&client).await,
+            Err(Error::HttpError(StatusCode::TOO_MANY_REQUESTS, _))
+        ));
+
+        mock.fault(Method::FindAll, Fault::Disconnect);
+        assert!(find_issues(&client).await.is_err());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].method, "tx");
+        assert_eq!(requests[1].params[0], "tracker:class:Issue");
+        assert_eq!(requests[1].token.as_deref(), Some("token"));
+    }
+
+    #[tokio::test]
+    async fn test_ws_faults() {
+        let mock = MockTransactor::start(MemoryBackend::new(uuid::Uuid::nil()))
+            .await
+            .unwrap();
+
+        mock.fault(
+            Method::Hello,
+            Fault::Error(error_status(&Error::Other("Denied"))),
+        );
+        assert!(
+            TransactorClient::new_ws(mock.url(), uuid::Uuid::nil(), "token", Default::default())
+                .await
+                .is_err()
+        );
+
+        let opts = WsBackendOpts {
+            binary: true,
+            ..Default::default()
+        };
+        let client = TransactorClient::new_ws(mock.url(), uuid::Uuid::nil(), "token", opts)
+            .await
+            .unwrap();
+
+        let mut events = client.subscribe::<Issue>().await;
+        client
+            .tx::<_, Value>(issue("i1", json!({ "title": "i1" })))
+            .await
+            .unwrap();
+        assert!(matches!(events.next().await, Some(Ok(TxEvent::Created(_)))));
+
+        // A delayed reply doesn't hold back later ones
+        mock.fault(Method::FindAll, Fault::Delay(Duration::from_millis(200)));
+        let delayed = find_issues(&client);
+        let account = client.get::<Account>(Method::Account, []);
+        let (delayed, account) = tokio::join!(delayed, account);
+        assert_eq!(delayed.unwrap().total, 1);
+        assert_eq!(account.unwrap().primary_social_id, "mock");
+
+        mock.fault(
+            Method::FindAll,
+            Fault::Error(error_status(&Error::Other("Failed"))),
+        );
+        assert!(matches!(
+            find_issues(&client).await,
+            Err(Error::ServiceError(_))
+        ));
+
+        mock.fault(
+            Method::FindAll,
+            Fault::RateLimited {
+                retry_after: Duration::from_secs(1),
+            },
+        );
+        assert!(matches!(
+            find_issues(&client).await,
+            Err(Error::ServiceError(status)) if status.code == "platform:status:RateLimitExceeded"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_domain_requests() {
+        let mock = MockTransactor::start(MemoryBackend::new(uuid::Uuid::nil()))
+            .await
+            .unwrap();
+
+        let http = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+        let http = TransactorClient::new(http, mock.url(), uuid::Uuid::nil(), "token").unwrap();
+        let ws =
+            TransactorClient::new_ws(mock.url(), uuid::Uuid::nil(), "token", Default::default())
+                .await
+                .unwrap();
+
+        mock.respond("communication", "findMessages", json!([{ "id": "m1" }]));
+        mock.respond("communication", "findMessages", json!([{ "id": "m2" }]));
+
+        let params = json!({ "card": "c1" });
+        let first = http
+            .domain_request::<Value, _>("communication".into(), "findMessages", &params)
+            .await
+            .unwrap();
+        let second = ws
+            .domain_request::<Value, _>("communication".into(), "findMessages", &params)
+            .await
+            .unwrap();
+
+        assert_eq!(first.domain, "communication");
+        assert_eq!(first.value[0]["id"], "m1");
+        assert_eq!(second.value[0]["id"], "m2");
+        assert!(
+            ws.domain_request::<Value, _>("communication".into(), "findMessages", &params)
+                .await
+                .is_err()
+        );
+
+        let requests = mock
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == Method::Request.camel())
+            .collect::<Vec<_>>();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].params[1]["findMessages"]["params"], params);
+        assert_eq!(requests[1].transport, Transport::Ws);
+    }
+
+    #[tokio::test]
+    async fn test_ws_pause_and_reconnect() {
+        let mock = MockTransactor::start(MemoryBackend::new(uuid::Uuid::nil()))
+            .await
+            .unwrap();
+
+        let opts = WsBackendOpts {
+            upgrade_policy: UpgradePolicy::PauseAndReconnect,
+            pause_timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let client = TransactorClient::new_ws(mock.url(), uuid::Uuid::nil(), "token", opts)
+            .await
+            .unwrap();
+
+        let mut events = client.subscribe_workspace_events();
+
+        mock.backend().broadcast(upgrade_scheduled());
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event.event, WorkspaceEvent::UpgradeScheduled);
+
+        // Held back while the announced upgrade is pending, sent again once reconnected
+        let account = client.get::<Account>(Method::Account, []);
+        let disconnect = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            assert_eq!(mock.requests().len(), 1);
+            mock.disconnect();
+        };
+        let (account, ()) = tokio::join!(account, disconnect);
+        assert_eq!(account.unwrap().primary_social_id, "mock");
+
+        // Reconnects on its own when the server keeps the socket open
+        mock.backend().broadcast(upgrade_scheduled());
+        events.next().await.unwrap().unwrap();
+        let account = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.get::<Account>(Method::Account, []),
+        )
+        .await
+        .unwrap();
+        assert_eq!(account.unwrap().primary_social_id, "mock");
+
+        let methods = mock
+            .requests()
+            .into_iter()
+            .map(|request| request.method)
+            .collect::<Vec<_>>();
+        assert_eq!(methods, ["hello", "hello", "account", "hello", "account"]);
+    }
+
+    #[tokio::test]
+    async fn test_ws_reconnect_failures() {
+        let mock = MockTransactor::start(MemoryBackend::new(uuid::Uuid::nil()))
+            .await
+            .unwrap();
+
+        let opts = WsBackendOpts {
+            upgrade_policy: UpgradePolicy::PauseAndReconnect,
+            reconnect_timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let client = TransactorClient::new_ws(mock.url(), uuid::Uuid::nil(), "token", opts)
+            .await
+            .unwrap();
+        let mut events = client.subscribe_workspace_events();
+
+        // Sent before the upgrade and left unanswered, only the read is repeated
+        mock.fault(Method::Tx, Fault::Delay(Duration::from_secs(5)));
+        mock.fault(Method::FindAll, Fault::Delay(Duration::from_secs(5)));
+        let tx = client.tx::<_, Value>(issue("i1", json!({ "title": "i1" })));
+        let find = find_issues(&client);
+        let upgrade = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            mock.backend().broadcast(upgrade_scheduled());
+            events.next().await.unwrap().unwrap();
+            mock.disconnect();
+        };
+        let (tx, find, ()) = tokio::join!(tx, find, upgrade);
+        assert!(matches!(tx, Err(Error::CallInterrupted)));
+        assert_eq!(find.unwrap().total, 0);
+
+        // Held calls fail once the transactor stays away
+        mock.backend().broadcast(upgrade_scheduled());
+        events.next().await.unwrap().unwrap();
+        let account = client.get::<Account>(Method::Account, []);
+        let stop = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(mock);
+        };
+        let (account, ()) = tokio::join!(account, stop);
+        assert!(matches!(account, Err(Error::ReconnectTimeout)));
+    }
+}